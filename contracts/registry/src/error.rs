@@ -30,8 +30,27 @@ pub enum Error {
     /// Must be 64 characters or less;
     /// ascii alphanumeric, '-', or '_';
     /// start with a ascii alphabetic character;
-    /// and not be a Rust keyword
+    /// and not be a Rust keyword.
+    /// Scoped names such as `@namespace/name` follow these rules for both parts
     InvalidName = 12,
     /// Invalid Version. Must be valid cargo version
     InvalidVersion = 13,
+    /// Address is already a maintainer of the published contract
+    AlreadyMaintainer = 14,
+    /// Address is not a maintainer of the published contract
+    NoSuchMaintainer = 15,
+    /// Version has already been yanked
+    AlreadyYanked = 16,
+    /// No such namespace has been claimed
+    NoSuchNamespace = 17,
+    /// Only the namespace owner or its members can use names in the namespace
+    NamespaceMembersOnly = 18,
+    /// Address is already a member of the namespace
+    AlreadyMember = 19,
+    /// Address is not a member of the namespace
+    NoSuchMember = 20,
+    /// Version was published before the registry recorded metadata
+    NoSuchMetadata = 21,
+    /// Contract was deployed before the registry recorded deployments
+    NoSuchContractInfo = 22,
 }
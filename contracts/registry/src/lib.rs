@@ -1,7 +1,10 @@
 #![no_std]
 use loam_subcontract_core::{admin::Admin, Core};
 
-use registry::{contract::C as Contract_, wasm::W as Wasm, Deployable, Publishable, Redeployable};
+use registry::{
+    contract::C as Contract_, namespace::N as Namespace, wasm::W as Wasm, Deployable, Namespaced,
+    Publishable, Redeployable,
+};
 
 pub mod error;
 pub mod name;
@@ -18,7 +21,8 @@ pub use error::Error;
     Core(Admin),
     Publishable(Wasm),
     Deployable(Contract_),
-    Redeployable(Contract_)
+    Redeployable(Contract_),
+    Namespaced(Namespace)
 )]
 pub struct Contract;
 
@@ -1,17 +1,63 @@
-use loam_sdk::soroban_sdk::String;
+use loam_sdk::soroban_sdk::{env, String};
 
 use crate::error::Error;
 
+const MAX_SEGMENT_LENGTH: usize = 64;
+/// `@<namespace>/<name>`
+pub(crate) const MAX_LENGTH: usize = 2 * MAX_SEGMENT_LENGTH + 2;
+
+/// Whether `s` is a valid name, either plain like `token` or scoped to a namespace like
+/// `@ahalabs/token`
 pub(crate) fn is_valid(s: &String) -> bool {
-    if s.len() > 64 || s.is_empty() {
-        return false;
+    parse(s, |s| {
+        if let Some((namespace, name)) = split_scoped(s) {
+            is_valid_segment(namespace) && is_valid_segment(name)
+        } else {
+            is_valid_segment(s)
+        }
+    })
+    .unwrap_or_default()
+}
+
+pub(crate) fn validate(s: &String) -> Result<(), Error> {
+    is_valid(s).then_some(()).ok_or(Error::InvalidName)
+}
+
+/// Validate a namespace, which follows the rules of a plain name
+pub(crate) fn validate_namespace(s: &String) -> Result<(), Error> {
+    parse(s, is_valid_segment)
+        .unwrap_or_default()
+        .then_some(())
+        .ok_or(Error::InvalidName)
+}
+
+/// Namespace of a valid scoped name such as `@ahalabs/token`, without the `@`
+pub(crate) fn namespace(s: &String) -> Result<Option<String>, Error> {
+    validate(s)?;
+    parse(s, |s| {
+        split_scoped(s).map(|(namespace, _)| String::from_str(env(), namespace))
+    })
+    .ok_or(Error::InvalidName)
+}
+
+fn parse<T>(s: &String, f: impl FnOnce(&str) -> T) -> Option<T> {
+    if s.len() as usize > MAX_LENGTH || s.is_empty() {
+        return None;
     }
-    let mut out = [0u8; 64];
+    let mut out = [0u8; MAX_LENGTH];
     let (first, _) = out.split_at_mut(s.len() as usize);
     s.copy_into_slice(first);
-    let Ok(s) = core::str::from_utf8(first) else {
+    core::str::from_utf8(first).ok().map(f)
+}
+
+fn split_scoped(s: &str) -> Option<(&str, &str)> {
+    s.strip_prefix('@')?.split_once('/')
+}
+
+fn is_valid_segment(s: &str) -> bool {
+    if s.len() > MAX_SEGMENT_LENGTH || s.is_empty() {
         return false;
-    };
+    }
     if is_keyword(s) || !s.starts_with(|c: char| c.is_ascii_alphabetic()) {
         return false;
     }
@@ -19,10 +65,6 @@ pub(crate) fn is_valid(s: &String) -> bool {
         .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
 }
 
-pub(crate) fn validate(s: &String) -> Result<(), Error> {
-    is_valid(s).then_some(()).ok_or(Error::InvalidName)
-}
-
 /// from crate `check_keyword`
 /// <https://github.com/JoelCourtney/check_keyword/blob/68486cbfa368070fdbfd383fc5840aa380bb1e6f/src/lib.rs#L120>
 fn is_keyword(s: &str) -> bool {
@@ -3,27 +3,121 @@ use loam_sdk::soroban_sdk::Lazy;
 use crate::error::Error;
 
 pub mod contract;
+pub mod namespace;
 pub mod wasm;
 
 pub use contract::C;
+pub use namespace::N;
 pub use wasm::W;
 
 #[loam_sdk::subcontract]
 pub trait IsPublishable {
-    /// Fetch the hash of a Wasm binary from the registry
+    /// Fetch the hash of a Wasm binary from the registry.
+    /// `version` may be an exact version or a semver requirement such as `^1.2` or `>=2, <3`,
+    /// which resolves to the greatest matching version that has not been yanked.
+    /// Defaults to the latest version
     fn fetch_hash(
         &self,
         wasm_name: loam_sdk::soroban_sdk::String,
         version: Option<loam_sdk::soroban_sdk::String>,
     ) -> Result<loam_sdk::soroban_sdk::BytesN<32>, Error>;
 
-    /// Most recent version of the published Wasm binary
+    /// Greatest published version of the Wasm binary by semver ordering, excluding yanked
+    /// versions and prereleases. This is the version `fetch_hash` returns without a version
     fn current_version(
         &self,
         wasm_name: loam_sdk::soroban_sdk::String,
     ) -> Result<loam_sdk::soroban_sdk::String, Error>;
 
-    /// Publish a binary. If contract had been previously published only previous author can publish again
+    /// Names of published Wasm binaries in the order they were first published.
+    /// Returns at most `limit` names starting at index `cursor`; pass the number of names
+    /// already seen as the cursor of the next page.
+    fn list_wasms(
+        &self,
+        cursor: Option<u32>,
+        limit: Option<u32>,
+    ) -> loam_sdk::soroban_sdk::Vec<loam_sdk::soroban_sdk::String>;
+
+    /// All published versions of a Wasm binary, from oldest to newest
+    fn list_versions(
+        &self,
+        wasm_name: loam_sdk::soroban_sdk::String,
+    ) -> Result<loam_sdk::soroban_sdk::Vec<loam_sdk::soroban_sdk::String>, Error>;
+
+    /// Yank a published version so it is no longer resolved as the latest version.
+    /// It can still be fetched and deployed by its exact version. Must be signed by the author
+    fn yank(
+        &mut self,
+        wasm_name: loam_sdk::soroban_sdk::String,
+        version: loam_sdk::soroban_sdk::String,
+        reason: loam_sdk::soroban_sdk::String,
+    ) -> Result<(), Error>;
+
+    /// Reason a version was yanked, if it was
+    fn fetch_yank_reason(
+        &self,
+        wasm_name: loam_sdk::soroban_sdk::String,
+        version: loam_sdk::soroban_sdk::String,
+    ) -> Result<Option<loam_sdk::soroban_sdk::String>, Error>;
+
+    /// Mark a published name as deprecated with a message shown to anyone deploying it.
+    /// An empty message lifts the deprecation. Must be signed by the author
+    fn deprecate(
+        &mut self,
+        wasm_name: loam_sdk::soroban_sdk::String,
+        message: loam_sdk::soroban_sdk::String,
+    ) -> Result<(), Error>;
+
+    /// Deprecation message of a published name, if it is deprecated
+    fn fetch_deprecation(
+        &self,
+        wasm_name: loam_sdk::soroban_sdk::String,
+    ) -> Result<Option<loam_sdk::soroban_sdk::String>, Error>;
+
+    /// Transfer a published name to a new author. Must be signed by the current author
+    fn transfer_author(
+        &mut self,
+        wasm_name: loam_sdk::soroban_sdk::String,
+        new_author: loam_sdk::soroban_sdk::Address,
+    ) -> Result<(), Error>;
+
+    /// Allow another address to publish new versions. Must be signed by the author
+    fn add_maintainer(
+        &mut self,
+        wasm_name: loam_sdk::soroban_sdk::String,
+        maintainer: loam_sdk::soroban_sdk::Address,
+    ) -> Result<(), Error>;
+
+    /// Revoke a maintainer's permission to publish new versions. Must be signed by the author
+    fn remove_maintainer(
+        &mut self,
+        wasm_name: loam_sdk::soroban_sdk::String,
+        maintainer: loam_sdk::soroban_sdk::Address,
+    ) -> Result<(), Error>;
+
+    /// Record the latest version of names published before the registry tracked it, which
+    /// were resolved by string rather than semver ordering. Prereleases are never the latest
+    /// version. Admin only
+    fn migrate_latest_versions(
+        &mut self,
+        wasm_names: loam_sdk::soroban_sdk::Vec<loam_sdk::soroban_sdk::String>,
+    ) -> Result<(), Error>;
+
+    /// Add names published before the registry listed them to `list_wasms`, emitting a `listed`
+    /// event for each. Names that are already listed are skipped. Admin only
+    fn migrate_name_index(
+        &mut self,
+        wasm_names: loam_sdk::soroban_sdk::Vec<loam_sdk::soroban_sdk::String>,
+    ) -> Result<(), Error>;
+
+    /// Metadata recorded when a version was published. Defaults to the latest version
+    fn get_metadata(
+        &self,
+        wasm_name: loam_sdk::soroban_sdk::String,
+        version: Option<loam_sdk::soroban_sdk::String>,
+    ) -> Result<crate::registry::wasm::Metadata, Error>;
+
+    /// Publish a binary. If contract had been previously published only its author or a maintainer can publish again
     fn publish(
         &mut self,
         wasm_name: loam_sdk::soroban_sdk::String,
@@ -32,7 +126,7 @@ pub trait IsPublishable {
         version: loam_sdk::soroban_sdk::String,
     ) -> Result<(), Error>;
 
-    /// Publish a binary. If contract had been previously published only previous author can publish again
+    /// Publish a binary. If contract had been previously published only its author or a maintainer can publish again
     fn publish_hash(
         &mut self,
         wasm_name: loam_sdk::soroban_sdk::String,
@@ -40,12 +134,23 @@ pub trait IsPublishable {
         wasm_hash: loam_sdk::soroban_sdk::BytesN<32>,
         version: loam_sdk::soroban_sdk::String,
     ) -> Result<(), Error>;
+
+    /// Publish a binary like `publish`, recording descriptive metadata for the version
+    fn publish_with_metadata(
+        &mut self,
+        wasm_name: loam_sdk::soroban_sdk::String,
+        author: loam_sdk::soroban_sdk::Address,
+        wasm: loam_sdk::soroban_sdk::Bytes,
+        version: loam_sdk::soroban_sdk::String,
+        metadata: crate::registry::wasm::PublishMetadata,
+    ) -> Result<(), Error>;
 }
 
 #[loam_sdk::subcontract]
 pub trait IsDeployable {
     /// Deploys a new published contract returning the deployed contract's id.
     /// If no salt provided it will use the current sequence number.
+    /// `version` is resolved the same way as in `fetch_hash`.
     fn deploy(
         &mut self,
         wasm_name: loam_sdk::soroban_sdk::String,
@@ -60,6 +165,33 @@ pub trait IsDeployable {
         &self,
         contract_name: loam_sdk::soroban_sdk::String,
     ) -> Result<loam_sdk::soroban_sdk::Address, Error>;
+
+    /// Give a name to a contract deployed outside the registry. `admin` manages the name. Must
+    /// also be signed by the contract's admin, or by the registry's admin if the contract has none
+    fn register_existing(
+        &mut self,
+        contract_name: loam_sdk::soroban_sdk::String,
+        contract_id: loam_sdk::soroban_sdk::Address,
+        admin: loam_sdk::soroban_sdk::Address,
+    ) -> Result<(), Error>;
+
+    /// Point a name at a different contract, e.g. one redeployed after a storage layout change.
+    /// Must be signed by the name's admin and by whoever controls the new contract, as in
+    /// `register_existing`
+    fn repoint(
+        &mut self,
+        contract_name: loam_sdk::soroban_sdk::String,
+        contract_id: loam_sdk::soroban_sdk::Address,
+    ) -> Result<(), Error>;
+
+    /// Release a name so it can be deployed or registered again. Must be signed by the name's admin
+    fn release(&mut self, contract_name: loam_sdk::soroban_sdk::String) -> Result<(), Error>;
+
+    /// Look up which published Wasm a contract runs, and when and by whom it was deployed
+    fn fetch_contract_info(
+        &self,
+        contract_name: loam_sdk::soroban_sdk::String,
+    ) -> Result<crate::registry::contract::ContractInfo, Error>;
 }
 
 #[loam_sdk::subcontract]
@@ -82,3 +214,40 @@ pub trait IsRedeployable {
         upgrade_fn: Option<loam_sdk::soroban_sdk::Symbol>,
     ) -> Result<loam_sdk::soroban_sdk::Address, Error>;
 }
+
+#[loam_sdk::subcontract]
+pub trait IsNamespaced {
+    /// Claim a namespace so only its owner and members can publish and deploy names such as
+    /// `@namespace/name`
+    fn claim_namespace(
+        &mut self,
+        namespace: loam_sdk::soroban_sdk::String,
+        owner: loam_sdk::soroban_sdk::Address,
+    ) -> Result<(), Error>;
+
+    /// Allow another address to use the namespace. Must be signed by the owner
+    fn add_namespace_member(
+        &mut self,
+        namespace: loam_sdk::soroban_sdk::String,
+        member: loam_sdk::soroban_sdk::Address,
+    ) -> Result<(), Error>;
+
+    /// Revoke a member's access to the namespace. Must be signed by the owner
+    fn remove_namespace_member(
+        &mut self,
+        namespace: loam_sdk::soroban_sdk::String,
+        member: loam_sdk::soroban_sdk::Address,
+    ) -> Result<(), Error>;
+
+    /// Look up the owner of a namespace
+    fn fetch_namespace_owner(
+        &self,
+        namespace: loam_sdk::soroban_sdk::String,
+    ) -> Result<loam_sdk::soroban_sdk::Address, Error>;
+
+    /// Look up the members of a namespace, not including its owner
+    fn fetch_namespace_members(
+        &self,
+        namespace: loam_sdk::soroban_sdk::String,
+    ) -> Result<loam_sdk::soroban_sdk::Vec<loam_sdk::soroban_sdk::Address>, Error>;
+}
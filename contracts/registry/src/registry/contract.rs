@@ -12,12 +12,11 @@ use loam_subcontract_core::Core;
 use crate::{
     error::Error,
     name::validate,
-    registry::Publishable,
-    util::{hash_string, REGISTRY},
+    util::{deploy_salt, REGISTRY},
     Contract as Contract_,
 };
 
-use super::{wasm::W, IsDeployable, IsRedeployable};
+use super::{wasm::W, IsDeployable, IsRedeployable, N};
 
 #[contracttype]
 pub struct DeployEventData {
@@ -28,9 +27,64 @@ pub struct DeployEventData {
     contract_id: Address,
 }
 
+/// What a named contract is running and who deployed it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractInfo {
+    pub contract_id: Address,
+    /// Published wasm the contract runs, `None` after a `dev_deploy` of unpublished wasm
+    pub wasm_name: Option<String>,
+    pub version: Option<String>,
+    /// `None` for contracts registered or repointed rather than deployed by the registry
+    pub wasm_hash: Option<BytesN<32>>,
+    /// `None` for contracts deployed before the registry recorded deployments
+    pub admin: Option<Address>,
+    /// Ledger the name was deployed or registered in, `None` for contracts deployed before the
+    /// registry recorded deployments
+    pub deployed_ledger: Option<u32>,
+    /// Ledger of the most recent deploy, upgrade or repoint
+    pub updated_ledger: u32,
+}
+
+#[contracttype]
+pub struct UpgradeEventData {
+    contract_name: String,
+    /// `None` after a `dev_deploy` of unpublished wasm
+    wasm_name: Option<String>,
+    version: Option<String>,
+    wasm_hash: BytesN<32>,
+    contract_id: Address,
+}
+
+#[contracttype]
+pub struct RegisterEventData {
+    contract_name: String,
+    admin: Address,
+    contract_id: Address,
+}
+
+#[contracttype]
+pub struct RepointEventData {
+    contract_name: String,
+    admin: Address,
+    previous_contract_id: Address,
+    contract_id: Address,
+}
+
+#[contracttype]
+pub struct ReleaseEventData {
+    contract_name: String,
+    admin: Address,
+    contract_id: Address,
+}
+
 #[loamstorage]
 pub struct C {
     pub r: PersistentMap<String, Address>,
+    /// Provenance of each deployed contract
+    pub i: PersistentMap<String, ContractInfo>,
+    /// Number of times each name has been released
+    pub g: PersistentMap<String, u32>,
 }
 
 impl C {
@@ -41,17 +95,92 @@ impl C {
         upgrade_fn: Option<Symbol>,
     ) -> Result<Address, Error> {
         let contract_id = self.fetch_contract_id(name.clone())?;
-        if let Ok(Ok(author)) = env().try_invoke_contract::<Address, Error>(
-            &contract_id,
-            &symbol_short!("admin"),
-            vec![],
-        ) {
+        if let Some(author) = contract_admin(&contract_id) {
             author.require_auth();
         }
         let fn_name = upgrade_fn.unwrap_or_else(|| symbol_short!("upgrade"));
         env().invoke_contract::<()>(&contract_id, &fn_name, vec![wasm_hash.into_val(env())]);
         Ok(contract_id)
     }
+
+    /// Record that `name` now runs `wasm_hash`, keeping when and by whom it was deployed, and
+    /// publish an upgrade event
+    fn record_upgrade(
+        &mut self,
+        name: &String,
+        contract_id: Address,
+        wasm: Option<(String, String)>,
+        wasm_hash: BytesN<32>,
+    ) {
+        let (admin, deployed_ledger) = self
+            .i
+            .get(name.clone())
+            .map(|info| (info.admin, info.deployed_ledger))
+            .unwrap_or_default();
+        let (wasm_name, version) = wasm.unzip();
+        self.i.set(
+            name.clone(),
+            &ContractInfo {
+                contract_id: contract_id.clone(),
+                wasm_name: wasm_name.clone(),
+                version: version.clone(),
+                wasm_hash: Some(wasm_hash.clone()),
+                admin,
+                deployed_ledger,
+                updated_ledger: env().ledger().sequence(),
+            },
+        );
+        env().events().publish(
+            (symbol_short!("upgrade"),),
+            UpgradeEventData {
+                contract_name: name.clone(),
+                wasm_name,
+                version,
+                wasm_hash,
+                contract_id,
+            },
+        );
+    }
+
+    /// Check that `name` is free and may be used by `admin`, who must have signed
+    fn validate_new_name(&self, name: &String, admin: &Address) -> Result<(), Error> {
+        validate(name)?;
+        if self.r.has(name.clone()) {
+            return Err(Error::AlreadyDeployed);
+        }
+        if *name == to_string(REGISTRY) {
+            assert_with_error!(
+                env(),
+                Contract_::admin_get().unwrap() == *admin,
+                Error::AdminOnly
+            );
+        }
+        // signed by owner
+        admin.require_auth();
+        N::default().authorize(name, admin)
+    }
+
+    /// Admin of the deployed contract `name`, who must have signed. Names deployed before the
+    /// registry recorded deployments are managed by the registry's admin.
+    fn authorized_admin(&self, name: &String) -> Result<(Address, ContractInfo), Error> {
+        let contract_id = self.fetch_contract_id(name.clone())?;
+        let info = self.i.get(name.clone()).unwrap_or_else(|| ContractInfo {
+            contract_id,
+            wasm_name: None,
+            version: None,
+            wasm_hash: None,
+            admin: None,
+            deployed_ledger: None,
+            updated_ledger: env().ledger().sequence(),
+        });
+        let admin = info
+            .admin
+            .clone()
+            .or_else(Contract_::admin_get)
+            .ok_or(Error::NoOwnerSet)?;
+        admin.require_auth();
+        Ok((admin, info))
+    }
 }
 
 impl IsDeployable for C {
@@ -63,27 +192,28 @@ impl IsDeployable for C {
         admin: Address,
         init: Option<soroban_sdk::Vec<soroban_sdk::Val>>,
     ) -> Result<Address, Error> {
-        validate(&contract_name)?;
+        self.validate_new_name(&contract_name, &admin)?;
         let env = env();
-        if self.r.has(contract_name.clone()) {
-            return Err(Error::AlreadyDeployed);
-        }
-        if contract_name == to_string(REGISTRY) {
-            assert_with_error!(
-                env,
-                Contract_::admin_get().unwrap() == admin,
-                Error::AdminOnly
-            );
-        }
-        // signed by owner
-        admin.require_auth();
-        let hash = Contract_::fetch_hash(wasm_name.clone(), version.clone())?;
-        let salt: BytesN<32> = hash_string(&contract_name).into();
-        let address = deploy_and_init(salt, hash, init);
+        let (version, hash) = W::default().resolve(&wasm_name, version)?;
+        let generation = self.g.get(contract_name.clone()).unwrap_or_default();
+        let salt = deploy_salt(&contract_name, generation)?;
+        let address = deploy_and_init(salt, hash.clone(), init);
         self.r.set(contract_name.clone(), &address);
+        let ledger = env.ledger().sequence();
+        self.i.set(
+            contract_name.clone(),
+            &ContractInfo {
+                contract_id: address.clone(),
+                wasm_name: Some(wasm_name.clone()),
+                version: Some(version.clone()),
+                wasm_hash: Some(hash),
+                admin: Some(admin.clone()),
+                deployed_ledger: Some(ledger),
+                updated_ledger: ledger,
+            },
+        );
 
         // Publish a deploy event
-        let version = version.map_or_else(|| W::default().most_recent_version(&wasm_name), Ok)?;
         let deploy_data = DeployEventData {
             wasm_name,
             contract_name,
@@ -102,6 +232,109 @@ impl IsDeployable for C {
             .get(contract_name)
             .ok_or(Error::NoSuchContractDeployed)
     }
+
+    fn register_existing(
+        &mut self,
+        contract_name: String,
+        contract_id: Address,
+        admin: Address,
+    ) -> Result<(), Error> {
+        self.validate_new_name(&contract_name, &admin)?;
+        require_controller(&contract_id, &admin)?;
+        self.r.set(contract_name.clone(), &contract_id);
+        let ledger = env().ledger().sequence();
+        self.i.set(
+            contract_name.clone(),
+            &ContractInfo {
+                contract_id: contract_id.clone(),
+                wasm_name: None,
+                version: None,
+                wasm_hash: None,
+                admin: Some(admin.clone()),
+                deployed_ledger: Some(ledger),
+                updated_ledger: ledger,
+            },
+        );
+        env().events().publish(
+            (symbol_short!("register"),),
+            RegisterEventData {
+                contract_name,
+                admin,
+                contract_id,
+            },
+        );
+        Ok(())
+    }
+
+    fn repoint(&mut self, contract_name: String, contract_id: Address) -> Result<(), Error> {
+        let (admin, info) = self.authorized_admin(&contract_name)?;
+        require_controller(&contract_id, &admin)?;
+        let previous_contract_id = info.contract_id.clone();
+        self.r.set(contract_name.clone(), &contract_id);
+        self.i.set(
+            contract_name.clone(),
+            &ContractInfo {
+                contract_id: contract_id.clone(),
+                wasm_name: None,
+                version: None,
+                wasm_hash: None,
+                updated_ledger: env().ledger().sequence(),
+                ..info
+            },
+        );
+        env().events().publish(
+            (symbol_short!("repoint"),),
+            RepointEventData {
+                contract_name,
+                admin,
+                previous_contract_id,
+                contract_id,
+            },
+        );
+        Ok(())
+    }
+
+    fn release(&mut self, contract_name: String) -> Result<(), Error> {
+        let (admin, info) = self.authorized_admin(&contract_name)?;
+        self.r.remove(contract_name.clone());
+        self.i.remove(contract_name.clone());
+        let generation = self.g.get(contract_name.clone()).unwrap_or_default();
+        self.g.set(contract_name.clone(), &(generation + 1));
+        env().events().publish(
+            (symbol_short!("release"),),
+            ReleaseEventData {
+                contract_name,
+                admin,
+                contract_id: info.contract_id,
+            },
+        );
+        Ok(())
+    }
+
+    fn fetch_contract_info(&self, contract_name: String) -> Result<ContractInfo, Error> {
+        self.fetch_contract_id(contract_name.clone())?;
+        self.i.get(contract_name).ok_or(Error::NoSuchContractInfo)
+    }
+}
+
+/// Admin reported by the contract's `admin` function, if it has one
+fn contract_admin(contract_id: &Address) -> Option<Address> {
+    env()
+        .try_invoke_contract::<Address, Error>(contract_id, &symbol_short!("admin"), vec![])
+        .ok()?
+        .ok()
+}
+
+/// Require that whoever controls `contract_id` signed: its own admin, or the registry's admin for
+/// contracts without one. `signer` has already signed this invocation
+fn require_controller(contract_id: &Address, signer: &Address) -> Result<(), Error> {
+    let controller = contract_admin(contract_id)
+        .or_else(Contract_::admin_get)
+        .ok_or(Error::NoOwnerSet)?;
+    if controller != *signer {
+        controller.require_auth();
+    }
+    Ok(())
 }
 
 fn deploy_and_init(
@@ -125,7 +358,9 @@ impl IsRedeployable for C {
         upgrade_fn: Option<soroban_sdk::Symbol>,
     ) -> Result<soroban_sdk::Address, Error> {
         let wasm_hash = env().deployer().upload_contract_wasm(wasm);
-        self.upgrade(&name, &wasm_hash, upgrade_fn)
+        let contract_id = self.upgrade(&name, &wasm_hash, upgrade_fn)?;
+        self.record_upgrade(&name, contract_id.clone(), None, wasm_hash);
+        Ok(contract_id)
     }
 
     fn upgrade_contract(
@@ -135,7 +370,14 @@ impl IsRedeployable for C {
         version: Option<String>,
         upgrade_fn: Option<Symbol>,
     ) -> Result<Address, Error> {
-        let wasm_hash = Contract_::fetch_hash(wasm_name.clone(), version.clone())?;
-        self.upgrade(&name, &wasm_hash, upgrade_fn)
+        let (version, wasm_hash) = W::default().resolve(&wasm_name, version)?;
+        let contract_id = self.upgrade(&name, &wasm_hash, upgrade_fn)?;
+        self.record_upgrade(
+            &name,
+            contract_id.clone(),
+            Some((wasm_name, version)),
+            wasm_hash,
+        );
+        Ok(contract_id)
     }
 }
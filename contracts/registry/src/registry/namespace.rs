@@ -0,0 +1,126 @@
+use loam_sdk::{
+    loamstorage,
+    soroban_sdk::{contracttype, env, symbol_short, Address, PersistentMap, String, Vec},
+};
+
+use crate::{
+    error::Error,
+    name::{self, validate_namespace},
+};
+
+use super::IsNamespaced;
+
+/// Namespaces for scoped names such as `@ahalabs/token`
+#[loamstorage]
+pub struct N {
+    /// Owner of each claimed namespace
+    pub o: PersistentMap<String, Address>,
+    /// Addresses other than the owner allowed to use each namespace
+    pub m: PersistentMap<String, Vec<Address>>,
+}
+
+#[contracttype]
+pub struct ClaimEventData {
+    namespace: String,
+    owner: Address,
+}
+
+#[contracttype]
+pub struct MemberEventData {
+    namespace: String,
+    owner: Address,
+    member: Address,
+}
+
+impl N {
+    fn owner(&self, namespace: &String) -> Result<Address, Error> {
+        self.o.get(namespace.clone()).ok_or(Error::NoSuchNamespace)
+    }
+
+    fn members(&self, namespace: &String) -> Vec<Address> {
+        self.m
+            .get(namespace.clone())
+            .unwrap_or_else(|| Vec::new(env()))
+    }
+
+    /// Current owner of `namespace`, who must have signed the transaction
+    fn authorized_owner(&self, namespace: &String) -> Result<Address, Error> {
+        let owner = self.owner(namespace)?;
+        owner.require_auth();
+        Ok(owner)
+    }
+
+    /// Check that `address` may use `name`. Plain names can be used by anyone, while scoped
+    /// names require `address` to be the owner or a member of the namespace.
+    pub fn authorize(&self, name: &String, address: &Address) -> Result<(), Error> {
+        let Some(namespace) = name::namespace(name)? else {
+            return Ok(());
+        };
+        if self.owner(&namespace)? == *address || self.members(&namespace).contains(address) {
+            Ok(())
+        } else {
+            Err(Error::NamespaceMembersOnly)
+        }
+    }
+}
+
+impl IsNamespaced for N {
+    fn claim_namespace(&mut self, namespace: String, owner: Address) -> Result<(), Error> {
+        owner.require_auth();
+        validate_namespace(&namespace)?;
+        if self.o.has(namespace.clone()) {
+            return Err(Error::AlreadyClaimed);
+        }
+        self.o.set(namespace.clone(), &owner);
+        env().events().publish(
+            (symbol_short!("claim"),),
+            ClaimEventData { namespace, owner },
+        );
+        Ok(())
+    }
+
+    fn add_namespace_member(&mut self, namespace: String, member: Address) -> Result<(), Error> {
+        let owner = self.authorized_owner(&namespace)?;
+        let mut members = self.members(&namespace);
+        if members.contains(&member) {
+            return Err(Error::AlreadyMember);
+        }
+        members.push_back(member.clone());
+        self.m.set(namespace.clone(), &members);
+        env().events().publish(
+            (symbol_short!("add_membr"),),
+            MemberEventData {
+                namespace,
+                owner,
+                member,
+            },
+        );
+        Ok(())
+    }
+
+    fn remove_namespace_member(&mut self, namespace: String, member: Address) -> Result<(), Error> {
+        let owner = self.authorized_owner(&namespace)?;
+        let mut members = self.members(&namespace);
+        let index = members.first_index_of(&member).ok_or(Error::NoSuchMember)?;
+        members.remove(index);
+        self.m.set(namespace.clone(), &members);
+        env().events().publish(
+            (symbol_short!("rm_membr"),),
+            MemberEventData {
+                namespace,
+                owner,
+                member,
+            },
+        );
+        Ok(())
+    }
+
+    fn fetch_namespace_owner(&self, namespace: String) -> Result<Address, Error> {
+        self.owner(&namespace)
+    }
+
+    fn fetch_namespace_members(&self, namespace: String) -> Result<Vec<Address>, Error> {
+        self.owner(&namespace)?;
+        Ok(self.members(&namespace))
+    }
+}
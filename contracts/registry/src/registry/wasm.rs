@@ -1,20 +1,117 @@
 use loam_sdk::{
     loamstorage,
-    soroban_sdk::{self, env, to_string, Address, BytesN, Map, PersistentMap, String},
+    soroban_sdk::{
+        self, contracttype, env, symbol_short, to_string, Address, BytesN, Map, PersistentItem,
+        PersistentMap, String, Vec,
+    },
 };
 use loam_subcontract_core::Core as _;
 
 use crate::{error::Error, name::validate, util::REGISTRY};
 
-use super::IsPublishable;
+use super::{IsPublishable, N};
+
+extern crate alloc;
 
 /// Contains
 #[loamstorage]
 pub struct W {
     pub r: PersistentMap<String, Map<String, BytesN<32>>>,
     pub a: PersistentMap<String, Address>,
+    /// Published names by the order they were first published in
+    pub n: PersistentMap<u32, String>,
+    /// Number of entries in `n`
+    pub c: PersistentItem<u32>,
+    /// Position of each name in `n`
+    pub x: PersistentMap<String, u32>,
+    /// Addresses other than the author allowed to publish new versions
+    pub m: PersistentMap<String, Vec<Address>>,
+    /// Yanked versions and the reason they were yanked
+    pub y: PersistentMap<String, Map<String, String>>,
+    /// Deprecation messages
+    pub d: PersistentMap<String, String>,
+    /// Greatest published version of each name that is not a prerelease
+    pub l: PersistentMap<String, String>,
+    /// Metadata of each published version
+    pub i: PersistentMap<String, Map<String, Metadata>>,
+}
+
+/// Descriptive metadata provided by the publisher, usually read from the wasm's contract meta
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PublishMetadata {
+    pub repository: Option<String>,
+    pub homepage: Option<String>,
+    pub license: Option<String>,
+    pub description: Option<String>,
+    pub scaffold_version: Option<String>,
+}
+
+/// Metadata recorded for each published version
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Metadata {
+    pub version: String,
+    pub repository: Option<String>,
+    pub homepage: Option<String>,
+    pub license: Option<String>,
+    pub description: Option<String>,
+    pub scaffold_version: Option<String>,
+    /// Ledger sequence the version was published in
+    pub published_ledger: u32,
+    pub publisher: Address,
+}
+
+#[contracttype]
+pub struct PublishEventData {
+    wasm_name: String,
+    version: String,
+    author: Address,
+    wasm_hash: BytesN<32>,
+}
+
+#[contracttype]
+pub struct LatestEventData {
+    wasm_name: String,
+    version: String,
+}
+
+#[contracttype]
+pub struct ListedEventData {
+    wasm_name: String,
+    index: u32,
+}
+
+#[contracttype]
+pub struct YankEventData {
+    wasm_name: String,
+    version: String,
+    reason: String,
+}
+
+#[contracttype]
+pub struct DeprecateEventData {
+    wasm_name: String,
+    message: String,
+}
+
+#[contracttype]
+pub struct TransferEventData {
+    wasm_name: String,
+    previous_author: Address,
+    new_author: Address,
 }
 
+#[contracttype]
+pub struct MaintainerEventData {
+    wasm_name: String,
+    author: Address,
+    maintainer: Address,
+}
+
+/// Most names returned by a single `list_wasms` call
+pub const MAX_PAGE_SIZE: u32 = 100;
+
 impl W {
     pub fn new(name: &String, author: Address) -> Self {
         let mut s = Self::default();
@@ -29,35 +126,227 @@ impl W {
             .get(name.clone())
             .ok_or(Error::NoSuchContractPublished)
     }
+    /// Greatest published version of `name` that is not a prerelease
     pub fn most_recent_version(&self, name: &String) -> Result<String, Error> {
-        self.registry(name)?
-            .keys()
-            .first()
+        if let Some(version) = self.l.get(name.clone()) {
+            return Ok(version);
+        }
+        // Published before the latest version was tracked, see `migrate_latest_versions`
+        self.greatest_version(&self.registry(name)?, |parsed, _| parsed.pre.is_empty())?
             .ok_or(Error::NoSuchVersion)
     }
 
     pub fn get(&self, name: &String, version: Option<String>) -> Result<BytesN<32>, Error> {
+        Ok(self.resolve(name, version)?.1)
+    }
+
+    /// Resolve `version` to a published version and its hash. An exact version resolves to itself,
+    /// even if yanked. A semver requirement such as `^1.2` resolves to the greatest matching version
+    /// that has not been yanked; like Cargo, only requirements that name a prerelease of the same
+    /// version match prereleases. `None` resolves to the latest version that is neither yanked nor
+    /// a prerelease.
+    pub fn resolve(
+        &self,
+        name: &String,
+        version: Option<String>,
+    ) -> Result<(String, BytesN<32>), Error> {
         let registry = self.registry(name)?;
-        if let Some(version) = version {
-            registry.get(version)
-        } else {
-            registry.values().last()
+        let version = match version {
+            Some(version) if crate::version::parse(&version).is_ok() => Some(version),
+            Some(req) => {
+                let req = crate::version::parse_req(&req)?;
+                self.greatest_version(&registry, |parsed, version| {
+                    req.matches(parsed) && self.yank_reason(name, version).is_none()
+                })?
+            }
+            None => {
+                let latest = self.most_recent_version(name)?;
+                if self.yank_reason(name, &latest).is_none() {
+                    Some(latest)
+                } else {
+                    self.greatest_version(&registry, |parsed, version| {
+                        parsed.pre.is_empty() && self.yank_reason(name, version).is_none()
+                    })?
+                }
+            }
+        }
+        .ok_or(Error::NoSuchVersion)?;
+        let hash = registry.get(version.clone()).ok_or(Error::NoSuchVersion)?;
+        Ok((version, hash))
+    }
+
+    /// Greatest published version by semver ordering that satisfies `filter`
+    fn greatest_version(
+        &self,
+        registry: &Map<String, BytesN<32>>,
+        filter: impl Fn(&semver::Version, &String) -> bool,
+    ) -> Result<Option<String>, Error> {
+        let mut best: Option<(semver::Version, String)> = None;
+        for version in registry.keys() {
+            let parsed = crate::version::parse(&version)?;
+            if !filter(&parsed, &version) {
+                continue;
+            }
+            if best.as_ref().is_none_or(|(best, _)| parsed > *best) {
+                best = Some((parsed, version));
+            }
         }
-        .ok_or(Error::NoSuchVersion)
+        Ok(best.map(|(_, version)| version))
+    }
+
+    fn record_metadata(
+        &mut self,
+        name: &String,
+        version: &String,
+        publisher: Address,
+        metadata: PublishMetadata,
+    ) {
+        let mut versions = self.i.get(name.clone()).unwrap_or_else(|| Map::new(env()));
+        let PublishMetadata {
+            repository,
+            homepage,
+            license,
+            description,
+            scaffold_version,
+        } = metadata;
+        versions.set(
+            version.clone(),
+            Metadata {
+                version: version.clone(),
+                repository,
+                homepage,
+                license,
+                description,
+                scaffold_version,
+                published_ledger: env().ledger().sequence(),
+                publisher,
+            },
+        );
+        self.i.set(name.clone(), &versions);
+    }
+
+    /// Publish `wasm_hash` as `version` of `wasm_name`, recording its metadata
+    fn publish_version(
+        &mut self,
+        wasm_name: String,
+        author: Address,
+        wasm_hash: BytesN<32>,
+        version: String,
+        metadata: PublishMetadata,
+    ) -> Result<(), Error> {
+        author.require_auth();
+        validate(&wasm_name)?;
+        if !self.can_publish(&wasm_name, &author) {
+            return Err(Error::AlreadyPublished);
+        }
+        if self.author(&wasm_name).is_none() {
+            N::default().authorize(&wasm_name, &author)?;
+        }
+        if wasm_name == to_string(REGISTRY) && crate::Contract::admin_get().unwrap() != author {
+            return Err(Error::AdminOnly);
+        }
+        self.validate_version(&version, &wasm_name)?;
+        if self.author(&wasm_name).is_none() {
+            self.a.set(wasm_name.clone(), &author);
+        }
+        self.record_metadata(&wasm_name, &version, author.clone(), metadata);
+        self.set(&wasm_name, version.clone(), wasm_hash.clone())?;
+        env().events().publish(
+            (symbol_short!("publish"),),
+            PublishEventData {
+                wasm_name,
+                version,
+                author,
+                wasm_hash,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn yank_reason(&self, name: &String, version: &String) -> Option<String> {
+        self.y.get(name.clone())?.get(version.clone())
     }
 
     pub fn set(&mut self, name: &String, version: String, binary: BytesN<32>) -> Result<(), Error> {
+        // Names published before they were indexed are indexed when they are next published
+        self.index(name);
         let mut registry = self.r.get(name.clone()).unwrap_or_else(|| Map::new(env()));
-        registry.set(version, binary);
+        registry.set(version.clone(), binary);
         self.r.set(name.clone(), &registry);
+        if crate::version::parse(&version)?.pre.is_empty() {
+            self.l.set(name.clone(), &version);
+        }
         Ok(())
     }
 
+    /// Add `name` to the list of published names, unless it is already listed
+    /// Add `name` to the list of names, returning its position if it was not listed yet
+    fn index(&mut self, name: &String) -> Option<u32> {
+        if self.x.get(name.clone()).is_some() {
+            return None;
+        }
+        let count = self.c.get().unwrap_or_default();
+        self.n.set(count, name);
+        self.x.set(name.clone(), &count);
+        self.c.set(&(count + 1));
+        Some(count)
+    }
+
+    pub fn names(&self, cursor: u32, limit: u32) -> Vec<String> {
+        let mut names = Vec::new(env());
+        let end = cursor.saturating_add(limit.min(MAX_PAGE_SIZE));
+        for i in cursor..end {
+            let Some(name) = self.n.get(i) else {
+                break;
+            };
+            names.push_back(name);
+        }
+        names
+    }
+
+    /// Versions of `name` sorted by semver, oldest first
+    pub fn versions(&self, name: &String) -> Result<Vec<String>, Error> {
+        let mut parsed = alloc::vec::Vec::new();
+        for version in self.registry(name)?.keys() {
+            parsed.push((crate::version::parse(&version)?, version));
+        }
+        parsed.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        let mut sorted = Vec::new(env());
+        for (_, version) in parsed {
+            sorted.push_back(version);
+        }
+        Ok(sorted)
+    }
+
     pub fn author(&self, name: &String) -> Option<Address> {
         self.a.get(name.clone())
     }
 
+    pub fn maintainers(&self, name: &String) -> Vec<Address> {
+        self.m.get(name.clone()).unwrap_or_else(|| Vec::new(env()))
+    }
+
+    /// Whether `address` may publish new versions of `name`
+    pub fn can_publish(&self, name: &String, address: &Address) -> bool {
+        self.author(name).is_none_or(|author| author == *address)
+            || self.maintainers(name).contains(address)
+    }
+
+    /// Current author of `name`, who must have signed the transaction
+    fn authorized_author(&self, name: &String) -> Result<Address, Error> {
+        let author = self.author(name).ok_or(Error::NoSuchContractPublished)?;
+        author.require_auth();
+        Ok(author)
+    }
+
     fn validate_version(&self, version: &String, wasm_name: &String) -> Result<(), Error> {
+        if self
+            .r
+            .get(wasm_name.clone())
+            .is_some_and(|registry| registry.contains_key(version.clone()))
+        {
+            return Err(Error::VersionMustBeGreaterThanCurrent);
+        }
         let version = crate::version::parse(version)?;
         if let Ok(current_version) = self.most_recent_version(wasm_name) {
             if version <= crate::version::parse(&current_version)? {
@@ -70,7 +359,158 @@ impl W {
 
 impl IsPublishable for W {
     fn current_version(&self, contract_name: String) -> Result<String, Error> {
-        self.most_recent_version(&contract_name)
+        Ok(self.resolve(&contract_name, None)?.0)
+    }
+
+    fn migrate_latest_versions(&mut self, wasm_names: Vec<String>) -> Result<(), Error> {
+        crate::Contract::admin_get().unwrap().require_auth();
+        for name in wasm_names {
+            let registry = self.registry(&name)?;
+            let Some(latest) =
+                self.greatest_version(&registry, |parsed, _| parsed.pre.is_empty())?
+            else {
+                // Only prereleases have been published, so there is no latest version yet
+                self.l.remove(name);
+                continue;
+            };
+            self.l.set(name.clone(), &latest);
+            env().events().publish(
+                (symbol_short!("latest"),),
+                LatestEventData {
+                    wasm_name: name,
+                    version: latest,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    fn migrate_name_index(&mut self, wasm_names: Vec<String>) -> Result<(), Error> {
+        crate::Contract::admin_get().unwrap().require_auth();
+        for name in wasm_names {
+            self.registry(&name)?;
+            if let Some(index) = self.index(&name) {
+                env().events().publish(
+                    (symbol_short!("listed"),),
+                    ListedEventData {
+                        wasm_name: name,
+                        index,
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn list_wasms(&self, cursor: Option<u32>, limit: Option<u32>) -> Vec<String> {
+        self.names(cursor.unwrap_or_default(), limit.unwrap_or(MAX_PAGE_SIZE))
+    }
+
+    fn list_versions(&self, wasm_name: String) -> Result<Vec<String>, Error> {
+        self.versions(&wasm_name)
+    }
+
+    fn yank(&mut self, wasm_name: String, version: String, reason: String) -> Result<(), Error> {
+        self.authorized_author(&wasm_name)?;
+        if !self.registry(&wasm_name)?.contains_key(version.clone()) {
+            return Err(Error::NoSuchVersion);
+        }
+        let mut yanked = self
+            .y
+            .get(wasm_name.clone())
+            .unwrap_or_else(|| Map::new(env()));
+        if yanked.contains_key(version.clone()) {
+            return Err(Error::AlreadyYanked);
+        }
+        yanked.set(version.clone(), reason.clone());
+        self.y.set(wasm_name.clone(), &yanked);
+        env().events().publish(
+            (symbol_short!("yank"),),
+            YankEventData {
+                wasm_name,
+                version,
+                reason,
+            },
+        );
+        Ok(())
+    }
+
+    fn fetch_yank_reason(
+        &self,
+        wasm_name: String,
+        version: String,
+    ) -> Result<Option<String>, Error> {
+        if !self.registry(&wasm_name)?.contains_key(version.clone()) {
+            return Err(Error::NoSuchVersion);
+        }
+        Ok(self.yank_reason(&wasm_name, &version))
+    }
+
+    fn deprecate(&mut self, wasm_name: String, message: String) -> Result<(), Error> {
+        self.authorized_author(&wasm_name)?;
+        self.d.set(wasm_name.clone(), &message);
+        env().events().publish(
+            (symbol_short!("deprecate"),),
+            DeprecateEventData { wasm_name, message },
+        );
+        Ok(())
+    }
+
+    fn fetch_deprecation(&self, wasm_name: String) -> Result<Option<String>, Error> {
+        self.registry(&wasm_name)?;
+        Ok(self.d.get(wasm_name).filter(|message| !message.is_empty()))
+    }
+
+    fn transfer_author(&mut self, wasm_name: String, new_author: Address) -> Result<(), Error> {
+        let previous_author = self.authorized_author(&wasm_name)?;
+        self.a.set(wasm_name.clone(), &new_author);
+        env().events().publish(
+            (symbol_short!("transfer"),),
+            TransferEventData {
+                wasm_name,
+                previous_author,
+                new_author,
+            },
+        );
+        Ok(())
+    }
+
+    fn add_maintainer(&mut self, wasm_name: String, maintainer: Address) -> Result<(), Error> {
+        let author = self.authorized_author(&wasm_name)?;
+        let mut maintainers = self.maintainers(&wasm_name);
+        if maintainers.contains(&maintainer) {
+            return Err(Error::AlreadyMaintainer);
+        }
+        maintainers.push_back(maintainer.clone());
+        self.m.set(wasm_name.clone(), &maintainers);
+        env().events().publish(
+            (symbol_short!("add_maint"),),
+            MaintainerEventData {
+                wasm_name,
+                author,
+                maintainer,
+            },
+        );
+        Ok(())
+    }
+
+    fn remove_maintainer(&mut self, wasm_name: String, maintainer: Address) -> Result<(), Error> {
+        let author = self.authorized_author(&wasm_name)?;
+        let mut maintainers = self.maintainers(&wasm_name);
+        let index = maintainers
+            .first_index_of(&maintainer)
+            .ok_or(Error::NoSuchMaintainer)?;
+        maintainers.remove(index);
+        self.m.set(wasm_name.clone(), &maintainers);
+        env().events().publish(
+            (symbol_short!("rm_maint"),),
+            MaintainerEventData {
+                wasm_name,
+                author,
+                maintainer,
+            },
+        );
+        Ok(())
     }
 
     fn publish(
@@ -81,7 +521,13 @@ impl IsPublishable for W {
         version: String,
     ) -> Result<(), Error> {
         let wasm_hash = env().deployer().upload_contract_wasm(wasm);
-        self.publish_hash(wasm_name, author, wasm_hash, version)
+        self.publish_version(
+            wasm_name,
+            author,
+            wasm_hash,
+            version,
+            PublishMetadata::default(),
+        )
     }
 
     fn publish_hash(
@@ -91,19 +537,33 @@ impl IsPublishable for W {
         wasm_hash: soroban_sdk::BytesN<32>,
         version: String,
     ) -> Result<(), Error> {
-        author.require_auth();
-        validate(&wasm_name)?;
-        if let Some(current) = self.author(&wasm_name) {
-            if author != current {
-                return Err(Error::AlreadyPublished);
-            }
-        }
-        if wasm_name == to_string(REGISTRY) && crate::Contract::admin_get().unwrap() != author {
-            return Err(Error::AdminOnly);
-        }
-        self.validate_version(&version, &wasm_name)?;
-        self.a.set(wasm_name.clone(), &author);
-        self.set(&wasm_name, version, wasm_hash)
+        self.publish_version(
+            wasm_name,
+            author,
+            wasm_hash,
+            version,
+            PublishMetadata::default(),
+        )
+    }
+
+    fn publish_with_metadata(
+        &mut self,
+        wasm_name: soroban_sdk::String,
+        author: soroban_sdk::Address,
+        wasm: soroban_sdk::Bytes,
+        version: String,
+        metadata: PublishMetadata,
+    ) -> Result<(), Error> {
+        let wasm_hash = env().deployer().upload_contract_wasm(wasm);
+        self.publish_version(wasm_name, author, wasm_hash, version, metadata)
+    }
+
+    fn get_metadata(&self, wasm_name: String, version: Option<String>) -> Result<Metadata, Error> {
+        let (version, _) = self.resolve(&wasm_name, version)?;
+        self.i
+            .get(wasm_name)
+            .and_then(|versions| versions.get(version))
+            .ok_or(Error::NoSuchMetadata)
     }
 
     fn fetch_hash(
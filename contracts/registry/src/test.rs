@@ -1,8 +1,11 @@
-use crate::{error::Error, name::is_valid, SorobanContract__Client as SorobanContractClient};
+use crate::{
+    error::Error, name::is_valid, registry::wasm::PublishMetadata,
+    SorobanContract__Client as SorobanContractClient,
+};
 use assert_matches::assert_matches;
 use loam_sdk::soroban_sdk::{
-    self, env, set_env,
-    testutils::{Address as _, BytesN as _},
+    self, env, set_env, symbol_short,
+    testutils::{Address as _, BytesN as _, Events as _, MockAuth, MockAuthInvoke},
     to_string, Address, Bytes, BytesN, Env, IntoVal,
 };
 extern crate std;
@@ -122,6 +125,19 @@ fn validate_names() {
     test_string("_ab", false);
     test_string("-ab", false);
     test_string("1ab", false);
+    test_string("@ahalabs/token", true);
+    test_string("@aha-labs/token_a", true);
+    test_string(
+        "@abcdefghabcdefghabcdefghabcdefghabcdefghabcdefghabcdefghabcdefgh/abcdefghabcdefghabcdefghabcdefghabcdefghabcdefghabcdefghabcdefgh",
+        true,
+    );
+    test_string("@ahalabs", false);
+    test_string("@ahalabs/", false);
+    test_string("@/token", false);
+    test_string("ahalabs/token", false);
+    test_string("@ahalabs/token/a", false);
+    test_string("@1ahalabs/token", false);
+    test_string("@ahalabs/fn", false);
 }
 
 #[test]
@@ -148,3 +164,572 @@ fn validate_version() {
         Err(Ok(Error::VersionMustBeGreaterThanCurrent))
     );
 }
+
+#[test]
+fn lists_wasms_and_versions() {
+    let (client, address) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let first = &to_string("first");
+    let second = &to_string("second");
+    assert_eq!(client.list_wasms(&None, &None).len(), 0);
+    assert_matches!(
+        client.try_list_versions(first).unwrap_err(),
+        Ok(Error::NoSuchContractPublished)
+    );
+
+    for version in ["0.0.9", "0.0.10", "1.0.0"] {
+        let hash: BytesN<32> = BytesN::random(env);
+        client.publish_hash(first, address, &hash, &to_string(version));
+    }
+    client.publish_hash(second, address, &BytesN::random(env), &default_version());
+
+    assert_eq!(
+        client.list_wasms(&None, &None),
+        soroban_sdk::vec![env, first.clone(), second.clone()]
+    );
+    assert_eq!(
+        client.list_wasms(&Some(1), &Some(1)),
+        soroban_sdk::vec![env, second.clone()]
+    );
+    assert_eq!(client.list_wasms(&Some(2), &None).len(), 0);
+    assert_eq!(
+        client.list_versions(first),
+        soroban_sdk::vec![
+            env,
+            to_string("0.0.9"),
+            to_string("0.0.10"),
+            to_string("1.0.0")
+        ]
+    );
+}
+
+#[test]
+fn names_are_indexed_once() {
+    let (client, address) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let name = &to_string("hello");
+    for version in ["0.0.1", "0.0.2"] {
+        client.publish_hash(name, address, &BytesN::random(env), &to_string(version));
+    }
+    client.migrate_name_index(&soroban_sdk::vec![env, name.clone()]);
+    assert_eq!(
+        client.list_wasms(&None, &None),
+        soroban_sdk::vec![env, name.clone()]
+    );
+    assert_matches!(
+        client
+            .try_migrate_name_index(&soroban_sdk::vec![env, to_string("unknown")])
+            .unwrap_err(),
+        Ok(Error::NoSuchContractPublished)
+    );
+}
+
+#[test]
+fn migrating_the_name_index_emits_an_event() {
+    let (client, address) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let name = &to_string("hello");
+    client.publish_hash(name, address, &BytesN::random(env), &default_version());
+    // Unlist the name, as if it had been published before names were listed
+    env.as_contract(&client.address, || {
+        let mut wasm = crate::registry::W::default();
+        wasm.x.remove(name.clone());
+        wasm.n.remove(0);
+        wasm.c.set(&0);
+    });
+    assert_eq!(client.list_wasms(&None, &None), soroban_sdk::vec![env]);
+
+    client.migrate_name_index(&soroban_sdk::vec![env, name.clone()]);
+    let (contract_id, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(contract_id, client.address);
+    assert_eq!(topics, (symbol_short!("listed"),).into_val(env));
+    assert_eq!(
+        client.list_wasms(&None, &None),
+        soroban_sdk::vec![env, name.clone()]
+    );
+}
+
+#[test]
+fn maintainers_can_publish() {
+    let (client, author) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let name = &to_string("publisher");
+    let maintainer = &Address::generate(env);
+    let new_author = &Address::generate(env);
+    client.publish_hash(name, author, &BytesN::random(env), &default_version());
+
+    assert_eq!(
+        client.try_publish_hash(name, maintainer, &BytesN::random(env), &to_string("0.0.1")),
+        Err(Ok(Error::AlreadyPublished))
+    );
+    client.add_maintainer(name, maintainer);
+    assert_eq!(
+        client.try_add_maintainer(name, maintainer),
+        Err(Ok(Error::AlreadyMaintainer))
+    );
+    client.publish_hash(name, maintainer, &BytesN::random(env), &to_string("0.0.1"));
+
+    client.remove_maintainer(name, maintainer);
+    assert_eq!(
+        client.try_remove_maintainer(name, maintainer),
+        Err(Ok(Error::NoSuchMaintainer))
+    );
+    assert_eq!(
+        client.try_publish_hash(name, maintainer, &BytesN::random(env), &to_string("0.0.2")),
+        Err(Ok(Error::AlreadyPublished))
+    );
+
+    client.transfer_author(name, new_author);
+    assert_eq!(
+        client.try_publish_hash(name, author, &BytesN::random(env), &to_string("0.0.2")),
+        Err(Ok(Error::AlreadyPublished))
+    );
+    client.publish_hash(name, new_author, &BytesN::random(env), &to_string("0.0.2"));
+    assert_eq!(
+        client.try_transfer_author(&to_string("unpublished"), new_author),
+        Err(Ok(Error::NoSuchContractPublished))
+    );
+}
+
+#[test]
+fn yanked_versions_are_skipped_unless_pinned() {
+    let (client, address) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let name = &to_string("publisher");
+    let first: BytesN<32> = BytesN::random(env);
+    let second: BytesN<32> = BytesN::random(env);
+    let version = &to_string("0.0.1");
+    client.publish_hash(name, address, &first, &default_version());
+    client.publish_hash(name, address, &second, version);
+
+    let reason = &to_string("broken upgrade");
+    client.yank(name, version, reason);
+    assert_eq!(client.fetch_hash(name, &None), first);
+    assert_eq!(client.fetch_hash(name, &Some(version.clone())), second);
+    assert_eq!(
+        client.fetch_yank_reason(name, version),
+        Some(reason.clone())
+    );
+    assert_eq!(client.fetch_yank_reason(name, &default_version()), None);
+    assert_eq!(
+        client.try_yank(name, version, reason),
+        Err(Ok(Error::AlreadyYanked))
+    );
+    assert_eq!(
+        client.try_yank(name, &to_string("9.9.9"), reason),
+        Err(Ok(Error::NoSuchVersion))
+    );
+
+    client.yank(name, &default_version(), reason);
+    assert_eq!(
+        client.try_fetch_hash(name, &None),
+        Err(Ok(Error::NoSuchVersion))
+    );
+}
+
+#[test]
+fn deprecate_and_undeprecate() {
+    let (client, address) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let name = &to_string("publisher");
+    client.publish_hash(name, address, &BytesN::random(env), &default_version());
+    assert_eq!(client.fetch_deprecation(name), None);
+
+    let message = &to_string("use publisher-v2");
+    client.deprecate(name, message);
+    assert_eq!(client.fetch_deprecation(name), Some(message.clone()));
+    client.deprecate(name, &to_string(""));
+    assert_eq!(client.fetch_deprecation(name), None);
+    assert_eq!(
+        client.try_fetch_deprecation(&to_string("unpublished")),
+        Err(Ok(Error::NoSuchContractPublished))
+    );
+}
+
+#[test]
+fn resolves_version_requirements() {
+    let (client, address) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let name = &to_string("publisher");
+    let mut hashes = std::vec::Vec::new();
+    for version in [
+        "0.3.1",
+        "0.3.4",
+        "1.2.0",
+        "1.9.0",
+        "2.0.0-beta.1",
+        "2.0.0",
+        "2.4.0",
+    ] {
+        let hash: BytesN<32> = BytesN::random(env);
+        client.publish_hash(name, address, &hash, &to_string(version));
+        hashes.push(hash);
+    }
+    let fetch = |req: &str| client.fetch_hash(name, &Some(to_string(req)));
+
+    assert_eq!(fetch("~0.3.1"), hashes[1]);
+    assert_eq!(fetch("^1.2"), hashes[3]);
+    assert_eq!(fetch(">=2, <3"), hashes[6]);
+    assert_eq!(fetch("2.0.0"), hashes[5]);
+    assert_eq!(fetch("=2.0.0-beta.1"), hashes[4]);
+    assert_eq!(fetch("<2"), hashes[3]);
+
+    client.yank(name, &to_string("1.9.0"), &to_string("bad release"));
+    assert_eq!(fetch("^1.2"), hashes[2]);
+    assert_eq!(fetch("1.9.0"), hashes[3]);
+
+    assert_matches!(
+        client
+            .try_fetch_hash(name, &Some(to_string("^3")))
+            .unwrap_err(),
+        Ok(Error::NoSuchVersion)
+    );
+    assert_matches!(
+        client
+            .try_fetch_hash(name, &Some(to_string("not a version")))
+            .unwrap_err(),
+        Ok(Error::InvalidVersion)
+    );
+}
+
+#[test]
+fn latest_version_skips_prereleases() {
+    let (client, address) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let name = &to_string("publisher");
+    let mut hashes = std::vec::Vec::new();
+    for version in ["1.0.0", "2.0.0-beta.1"] {
+        let hash: BytesN<32> = BytesN::random(env);
+        client.publish_hash(name, address, &hash, &to_string(version));
+        hashes.push(hash);
+    }
+    assert_eq!(client.fetch_hash(name, &None), hashes[0]);
+    assert_eq!(client.current_version(name), to_string("1.0.0"));
+    assert_eq!(
+        client.fetch_hash(name, &Some(to_string("2.0.0-beta.1"))),
+        hashes[1]
+    );
+    assert_eq!(
+        client.try_publish_hash(
+            name,
+            address,
+            &BytesN::random(env),
+            &to_string("2.0.0-beta.1")
+        ),
+        Err(Ok(Error::VersionMustBeGreaterThanCurrent))
+    );
+}
+
+#[test]
+fn latest_version_uses_semver_ordering() {
+    let (client, address) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let name = &to_string("publisher");
+    let mut hashes = std::vec::Vec::new();
+    for version in ["0.9.0", "0.10.0", "0.10.1"] {
+        let hash: BytesN<32> = BytesN::random(env);
+        client.publish_hash(name, address, &hash, &to_string(version));
+        hashes.push(hash);
+        assert_eq!(client.current_version(name), to_string(version));
+    }
+    assert_eq!(client.fetch_hash(name, &None), hashes[2]);
+    assert_eq!(
+        client.try_publish_hash(name, address, &BytesN::random(env), &to_string("0.9.5")),
+        Err(Ok(Error::VersionMustBeGreaterThanCurrent))
+    );
+
+    client.yank(name, &to_string("0.10.1"), &to_string("bad release"));
+    assert_eq!(client.fetch_hash(name, &None), hashes[1]);
+    assert_eq!(client.current_version(name), to_string("0.10.0"));
+    // Yanked versions still count when checking that new versions are greater
+    assert_eq!(
+        client.try_publish_hash(
+            name,
+            address,
+            &BytesN::random(env),
+            &to_string("0.10.1-rc.1")
+        ),
+        Err(Ok(Error::VersionMustBeGreaterThanCurrent))
+    );
+
+    client.migrate_latest_versions(&soroban_sdk::vec![env, name.clone()]);
+    assert_eq!(client.current_version(name), to_string("0.10.0"));
+
+    client.publish_hash(
+        name,
+        address,
+        &BytesN::random(env),
+        &to_string("0.11.0-rc.1"),
+    );
+    client.migrate_latest_versions(&soroban_sdk::vec![env, name.clone()]);
+    assert_eq!(client.current_version(name), to_string("0.10.0"));
+}
+
+#[test]
+fn scoped_names_require_namespace_membership() {
+    let (client, owner) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let member = &Address::generate(env);
+    let outsider = &Address::generate(env);
+    let namespace = &to_string("ahalabs");
+    let name = &to_string("@ahalabs/token");
+
+    assert_eq!(
+        client.try_publish_hash(name, owner, &BytesN::random(env), &default_version()),
+        Err(Ok(Error::NoSuchNamespace))
+    );
+    client.claim_namespace(namespace, owner);
+    assert_eq!(
+        client.try_claim_namespace(namespace, outsider),
+        Err(Ok(Error::AlreadyClaimed))
+    );
+    assert_eq!(
+        client.try_claim_namespace(&to_string("@ahalabs"), outsider),
+        Err(Ok(Error::InvalidName))
+    );
+    assert_eq!(client.fetch_namespace_owner(namespace), owner.clone());
+
+    assert_eq!(
+        client.try_publish_hash(name, outsider, &BytesN::random(env), &default_version()),
+        Err(Ok(Error::NamespaceMembersOnly))
+    );
+    client.add_namespace_member(namespace, member);
+    assert_eq!(
+        client.fetch_namespace_members(namespace),
+        soroban_sdk::vec![env, member.clone()]
+    );
+    client.publish_hash(name, member, &BytesN::random(env), &default_version());
+
+    client.remove_namespace_member(namespace, member);
+    assert_eq!(
+        client.try_remove_namespace_member(namespace, member),
+        Err(Ok(Error::NoSuchMember))
+    );
+    assert_eq!(
+        client.try_publish_hash(
+            &to_string("@ahalabs/nft"),
+            member,
+            &BytesN::random(env),
+            &default_version()
+        ),
+        Err(Ok(Error::NamespaceMembersOnly))
+    );
+}
+
+#[test]
+fn records_metadata_per_version() {
+    let (client, address) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let name = &to_string("publisher");
+    let metadata = PublishMetadata {
+        repository: Some(to_string("https://github.com/ahalabs/scaffold-stellar")),
+        license: Some(to_string("Apache-2.0")),
+        ..Default::default()
+    };
+    client.publish_with_metadata(
+        name,
+        address,
+        &Bytes::from_slice(env, registry::WASM),
+        &default_version(),
+        &metadata,
+    );
+    client.publish_hash(name, address, &BytesN::random(env), &to_string("0.0.1"));
+
+    let first = client.get_metadata(name, &Some(default_version()));
+    assert_eq!(first.version, default_version());
+    assert_eq!(first.repository, metadata.repository);
+    assert_eq!(first.license, metadata.license);
+    assert_eq!(first.homepage, None);
+    assert_eq!(first.publisher, address.clone());
+    assert_eq!(first.published_ledger, env.ledger().sequence());
+
+    let latest = client.get_metadata(name, &None);
+    assert_eq!(latest.version, to_string("0.0.1"));
+    assert_eq!(latest.repository, None);
+    assert_matches!(
+        client
+            .try_get_metadata(name, &Some(to_string("1.0.0")))
+            .unwrap_err(),
+        Ok(Error::NoSuchVersion)
+    );
+}
+
+#[test]
+fn longest_scoped_names_can_be_published_and_deployed() {
+    let (client, address) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let namespace = "n".repeat(64);
+    let name = &to_string(&std::format!("@{namespace}/{}", "t".repeat(64)));
+    assert!(is_valid(name));
+    client.claim_namespace(&to_string(&namespace), address);
+    let bytes = &Bytes::from_slice(env, registry::WASM);
+    client.publish(name, address, bytes, &default_version());
+
+    let contract_id = client.deploy(
+        name,
+        &None,
+        name,
+        address,
+        &Some(soroban_sdk::vec![env, address.into_val(env)]),
+    );
+    assert_eq!(client.fetch_contract_id(name), contract_id);
+    assert_eq!(
+        client.try_publish_hash(
+            &to_string(&std::format!("@{namespace}/{}", "t".repeat(65))),
+            address,
+            &BytesN::random(env),
+            &default_version()
+        ),
+        Err(Ok(Error::InvalidName))
+    );
+}
+
+#[test]
+fn records_deployed_contract_info() {
+    let (client, address) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let wasm_name = &to_string("registry");
+    let contract_name = &to_string("my-registry");
+    let bytes = &Bytes::from_slice(env, registry::WASM);
+    client.publish(wasm_name, address, bytes, &default_version());
+    assert_matches!(
+        client.try_fetch_contract_info(contract_name).unwrap_err(),
+        Ok(Error::NoSuchContractDeployed)
+    );
+
+    let contract_id = client.deploy(
+        wasm_name,
+        &None,
+        contract_name,
+        address,
+        &Some(soroban_sdk::vec![env, address.into_val(env)]),
+    );
+    let info = client.fetch_contract_info(contract_name);
+    assert_eq!(info.contract_id, contract_id);
+    assert_eq!(info.wasm_name, Some(wasm_name.clone()));
+    assert_eq!(info.version, Some(default_version()));
+    assert_eq!(info.wasm_hash, Some(client.fetch_hash(wasm_name, &None)));
+    assert_eq!(info.admin, Some(address.clone()));
+    assert_eq!(info.deployed_ledger, Some(env.ledger().sequence()));
+    assert_eq!(info.updated_ledger, env.ledger().sequence());
+}
+
+#[test]
+fn register_repoint_and_release() {
+    let (client, address) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let contract_name = &to_string("existing");
+    let contract_id = &Address::generate(env);
+    client.register_existing(contract_name, contract_id, address);
+    assert_eq!(client.fetch_contract_id(contract_name), *contract_id);
+    let info = client.fetch_contract_info(contract_name);
+    assert_eq!(info.wasm_name, None);
+    assert_eq!(info.wasm_hash, None);
+    assert_eq!(info.admin, Some(address.clone()));
+    assert_matches!(
+        client
+            .try_register_existing(contract_name, contract_id, address)
+            .unwrap_err(),
+        Ok(Error::AlreadyDeployed)
+    );
+
+    let new_id = &Address::generate(env);
+    client.repoint(contract_name, new_id);
+    assert_eq!(client.fetch_contract_id(contract_name), *new_id);
+    let info = client.fetch_contract_info(contract_name);
+    assert_eq!(info.contract_id, *new_id);
+    assert_eq!(info.admin, Some(address.clone()));
+
+    client.release(contract_name);
+    assert_matches!(
+        client.try_fetch_contract_id(contract_name).unwrap_err(),
+        Ok(Error::NoSuchContractDeployed)
+    );
+    assert_matches!(
+        client.try_repoint(contract_name, new_id).unwrap_err(),
+        Ok(Error::NoSuchContractDeployed)
+    );
+    assert_matches!(
+        client.try_release(contract_name).unwrap_err(),
+        Ok(Error::NoSuchContractDeployed)
+    );
+    client.register_existing(contract_name, contract_id, address);
+    assert_eq!(client.fetch_contract_id(contract_name), *contract_id);
+}
+
+#[test]
+fn registering_needs_whoever_controls_the_contract() {
+    let (client, address) = &init();
+    let env = env();
+    let contract_name = &to_string("someone-elses");
+    let contract_id = &Address::generate(env);
+    let other = &Address::generate(env);
+
+    // An unrelated address can sign for the name but not for the contract
+    env.mock_auths(&[MockAuth {
+        address: other,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "register_existing",
+            args: (contract_name.clone(), contract_id.clone(), other.clone()).into_val(env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client
+        .try_register_existing(contract_name, contract_id, other)
+        .is_err());
+    assert_matches!(
+        client.try_fetch_contract_id(contract_name).unwrap_err(),
+        Ok(Error::NoSuchContractDeployed)
+    );
+
+    // Contracts without an admin of their own need the registry's admin
+    env.mock_all_auths();
+    client.register_existing(contract_name, contract_id, other);
+    assert!(env.auths().iter().any(|(signer, _)| signer == address));
+    assert_eq!(client.fetch_contract_id(contract_name), *contract_id);
+}
+
+#[test]
+fn released_names_can_be_redeployed() {
+    let (client, address) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let wasm_name = &to_string("registry");
+    let contract_name = &to_string("my-registry");
+    let bytes = &Bytes::from_slice(env, registry::WASM);
+    client.publish(wasm_name, address, bytes, &default_version());
+    let init_args = &Some(soroban_sdk::vec![env, address.into_val(env)]);
+    let first = client.deploy(wasm_name, &None, contract_name, address, init_args);
+    client.release(contract_name);
+    let second = client.deploy(wasm_name, &None, contract_name, address, init_args);
+    assert_ne!(first, second);
+    assert_eq!(client.fetch_contract_id(contract_name), second);
+}
+
+#[test]
+fn publishing_emits_event() {
+    let (client, address) = &init();
+    let env = env();
+    env.mock_all_auths();
+    let name = &to_string("hello_world");
+    let hash = &BytesN::random(env);
+    client.publish_hash(name, address, hash, &default_version());
+    let (contract_id, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(contract_id, client.address);
+    assert_eq!(topics, (symbol_short!("publish"),).into_val(env));
+}
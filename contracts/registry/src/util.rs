@@ -1,14 +1,33 @@
-use loam_sdk::soroban_sdk::{crypto::Hash, env, Bytes, String};
+use loam_sdk::soroban_sdk::{crypto::Hash, env, Bytes, BytesN, String};
 
-pub fn hash_string(s: &String) -> Hash<32> {
+use crate::{error::Error, name::MAX_LENGTH};
+
+/// Hash of a name, which can be at most as long as the longest valid name
+pub fn hash_string(s: &String) -> Result<Hash<32>, Error> {
     let env = env();
     let len = s.len() as usize;
-    let mut bytes = [0u8; 100];
+    if len > MAX_LENGTH {
+        return Err(Error::InvalidName);
+    }
+    let mut bytes = [0u8; MAX_LENGTH];
     let bytes = &mut bytes[0..len];
     s.copy_into_slice(bytes);
     let mut b = Bytes::new(env);
     b.copy_from_slice(0, bytes);
-    env.crypto().sha256(&b)
+    Ok(env.crypto().sha256(&b))
+}
+
+/// Salt for deploying the contract `name`. Each time a name is released its generation is
+/// incremented, so deploying it again does not collide with the previous contract's address.
+pub fn deploy_salt(name: &String, generation: u32) -> Result<BytesN<32>, Error> {
+    let hash = hash_string(name)?;
+    if generation == 0 {
+        return Ok(hash.into());
+    }
+    let env = env();
+    let mut bytes = Bytes::from_array(env, &hash.to_array());
+    bytes.extend_from_array(&generation.to_be_bytes());
+    Ok(env.crypto().sha256(&bytes).into())
 }
 
 pub const MAX_BUMP: u32 = 535_679;
@@ -5,6 +5,15 @@ use crate::Error;
 const MAX_VERSION_LENGTH: usize = 200;
 
 pub fn parse(s: &String) -> Result<semver::Version, Error> {
+    with_str(s, |s| s.parse().map_err(|_| Error::InvalidVersion))
+}
+
+/// Parse a semver requirement such as `^1.2`, `~0.3.1` or `>=2, <3`
+pub fn parse_req(s: &String) -> Result<semver::VersionReq, Error> {
+    with_str(s, |s| s.parse().map_err(|_| Error::InvalidVersion))
+}
+
+fn with_str<T>(s: &String, f: impl FnOnce(&str) -> Result<T, Error>) -> Result<T, Error> {
     if s.len() as usize > MAX_VERSION_LENGTH || s.is_empty() {
         return Err(Error::InvalidVersion);
     }
@@ -14,5 +23,5 @@ pub fn parse(s: &String) -> Result<semver::Version, Error> {
     let Ok(s) = core::str::from_utf8(first) else {
         return Err(Error::InvalidVersion);
     };
-    s.parse().map_err(|_| Error::InvalidVersion)
+    f(s)
 }
@@ -29,13 +29,16 @@ use topological_sort::TopologicalSort;
 /// - There's an issue executing the metadata command.
 /// - Any other error occurs during the metadata retrieval process.
 pub fn get_target_dir(manifest_path: &Path) -> Result<PathBuf, cargo_metadata::Error> {
-    Ok(stellar_wasm_out_dir(
-        cargo_metadata::MetadataCommand::new()
-            .manifest_path(manifest_path)
-            .exec()?
-            .target_directory
-            .as_std_path(),
-    ))
+    Ok(stellar_wasm_out_dir(&cargo_target_dir(manifest_path)?))
+}
+
+/// Cargo's target directory for the workspace of `manifest_path`
+pub fn cargo_target_dir(manifest_path: &Path) -> Result<PathBuf, cargo_metadata::Error> {
+    Ok(cargo_metadata::MetadataCommand::new()
+        .manifest_path(manifest_path)
+        .exec()?
+        .target_directory
+        .into_std_path_buf())
 }
 
 pub trait PackageExt {
@@ -144,11 +147,17 @@ pub fn stellar_wasm_out_dir(target_dir: &Path) -> PathBuf {
     target_dir.join("stellar")
 }
 
+/// Wasm file of the contract `name`. A scoped name such as `@ahalabs/token` is placed in a
+/// directory named after its namespace, `ahalabs/token.wasm`
 #[must_use]
 pub fn stellar_wasm_out_file(target_dir: &Path, name: &str) -> PathBuf {
-    stellar_wasm_out_dir(target_dir)
-        .join(name.replace('-', "_"))
-        .with_extension("wasm")
+    let name = name.replace('-', "_");
+    let mut file = stellar_wasm_out_dir(target_dir);
+    match name.strip_prefix('@').and_then(|name| name.split_once('/')) {
+        Some((namespace, name)) => file.extend([namespace, name]),
+        None => file.push(name),
+    }
+    file.with_extension("wasm")
 }
 // /// Retrieves a list of source and output paths for dependencies of a specified kind.
 // ///
@@ -239,11 +248,53 @@ pub fn contract(manifest_path: &Path) -> Result<Vec<Package>, Error> {
 /// * There's an issue retrieving contract dependencies for any of the packages.
 /// * The dependency graph contains cycles, making topological sorting impossible.
 pub fn get_workspace(packages: &[Package]) -> Result<Vec<Package>, Error> {
+    Ok(sort_workspace(packages, &contract_dependencies(packages)?))
+}
+
+/// Retrieves the contract dependencies of each of the given packages.
+///
+/// These are the edges used by [`get_workspace`] to order the workspace; packages without an
+/// edge between them can be built and deployed independently of each other.
+///
+/// # Arguments
+///
+/// * `packages` - A slice of Package structs to process.
+///
+/// # Returns
+///
+/// A Result containing a map from each package's ID to the IDs of its contract dependencies,
+/// or an Error if the operation fails.
+///
+/// # Errors
+///
+/// This function will return an Error if there's an issue retrieving contract dependencies for
+/// any of the packages.
+pub fn contract_dependencies(
+    packages: &[Package],
+) -> Result<HashMap<PackageId, Vec<PackageId>>, Error> {
+    packages
+        .iter()
+        .map(|p| {
+            let deps = contract(&p.manifest_path.clone().into_std_path_buf())?
+                .into_iter()
+                .map(|dep| dep.id)
+                .collect();
+            Ok((p.id.clone(), deps))
+        })
+        .collect()
+}
+
+/// Sorts packages topologically using dependencies from [`contract_dependencies`], so that each
+/// contract comes after the contracts it depends on.
+#[must_use]
+pub fn sort_workspace(
+    packages: &[Package],
+    dependencies: &HashMap<PackageId, Vec<PackageId>>,
+) -> Vec<Package> {
     let mut graph: TopologicalSort<PackageId> = TopologicalSort::new();
     for p in packages {
-        let contract_deps = contract(&p.manifest_path.clone().into_std_path_buf())?;
-        for dep in contract_deps {
-            graph.add_dependency(dep.id.clone(), p.id.clone());
+        for dep in dependencies.get(&p.id).into_iter().flatten() {
+            graph.add_dependency(dep.clone(), p.id.clone());
         }
         graph.insert(p.id.clone());
     }
@@ -253,7 +304,7 @@ pub fn get_workspace(packages: &[Package]) -> Result<Vec<Package>, Error> {
             res.push(contract.clone());
         }
     }
-    Ok(res)
+    res
 }
 
 #[cfg(test)]
@@ -273,4 +324,17 @@ mod tests {
         let deps = all(&manifest_path).unwrap();
         println!("{deps:#?}\n{}", deps.len());
     }
+
+    #[test]
+    fn scoped_names_are_nested_by_namespace() {
+        let target_dir = Path::new("target");
+        assert_eq!(
+            stellar_wasm_out_file(target_dir, "hello-world"),
+            Path::new("target/stellar/hello_world.wasm")
+        );
+        assert_eq!(
+            stellar_wasm_out_file(target_dir, "@aha-labs/my-token"),
+            Path::new("target/stellar/aha_labs/my_token.wasm")
+        );
+    }
 }
@@ -0,0 +1,46 @@
+use clap::Parser;
+
+use stellar_cli::{commands::contract::invoke, fee};
+
+use crate::{contract::NetworkContract, registries};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Name of published wasm
+    pub wasm_name: String,
+    /// Address or key name of the maintainer
+    pub maintainer: String,
+    #[command(flatten)]
+    pub config: registries::Args,
+    #[command(flatten)]
+    pub fee: fee::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        self.config
+            .invoke_registry(
+                &[
+                    "add_maintainer",
+                    "--wasm_name",
+                    &self.wasm_name,
+                    "--maintainer",
+                    &self.maintainer,
+                ],
+                Some(&self.fee),
+                false,
+            )
+            .await?;
+        eprintln!(
+            "✅ Added {} as a maintainer of {:?}",
+            self.maintainer, self.wasm_name
+        );
+        Ok(())
+    }
+}
@@ -2,7 +2,6 @@
 use std::{ffi::OsString, path::PathBuf};
 
 use clap::Parser;
-use ed25519_dalek::SigningKey;
 
 use stellar_cli::{
     assembled::simulate_and_assemble_transaction,
@@ -19,24 +18,29 @@ use stellar_cli::{
 use soroban_rpc as rpc;
 pub use soroban_spec_tools::contract as contract_spec;
 
-use crate::contract::NetworkContract;
+use crate::{
+    contract::{unsigned_envelope, NetworkContract},
+    deprecation, registries,
+};
 
 #[derive(Parser, Debug, Clone)]
 pub struct Cmd {
     /// Name of contract to be deployed
     #[arg(long, visible_alias = "deploy-as")]
     pub contract_name: String,
-    /// Name of published contract to deploy from
+    /// Name of published contract to deploy from. Prefix it with a registry's alias, e.g.
+    /// `org:token`, to deploy from that registry
     #[arg(long)]
     pub wasm_name: String,
     /// Arguments for constructor
     #[arg(last = true, id = "CONSTRUCTOR_ARGS")]
     pub slop: Vec<OsString>,
-    /// Version of the wasm to deploy
+    /// Version of the wasm to deploy, either exact or a semver requirement such as `^1.2` or
+    /// `>=2, <3`. Defaults to the latest version
     #[arg(long)]
     pub version: Option<String>,
     #[command(flatten)]
-    pub config: config::Args,
+    pub config: registries::Args,
     #[command(flatten)]
     pub fee: fee::Args,
 }
@@ -50,6 +54,10 @@ pub enum Error {
     #[error(transparent)]
     Install(#[from] super::install::Error),
     #[error(transparent)]
+    Deprecation(#[from] deprecation::Error),
+    #[error(transparent)]
+    Registries(#[from] registries::Error),
+    #[error(transparent)]
     Rpc(#[from] rpc::Error),
     #[error(transparent)]
     SpecTools(#[from] soroban_spec_tools::Error),
@@ -74,25 +82,75 @@ pub enum Error {
     MissingFileArg(PathBuf),
     #[error("Missing argument {0}")]
     MissingArgument(String),
+    #[error(
+        "The constructor needs authorization from {0} signer(s) besides the source account, which \
+         cannot be added to a transaction built with --build-only. Deploy without --build-only"
+    )]
+    BuildOnlyWithSigners(usize),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
 }
 
 impl Cmd {
     pub async fn run(&self) -> Result<(), Error> {
+        let cmd = Self {
+            config: self.config.for_ref(&self.wasm_name)?,
+            ..self.clone()
+        };
+        cmd.deploy().await
+    }
+
+    async fn deploy(&self) -> Result<(), Error> {
+        deprecation::warn_if_deprecated(&self.config, self.published_name()).await?;
+        // Requirements never resolve to yanked versions, only exact versions can
+        if let Some(version) = self
+            .version
+            .as_deref()
+            .filter(|version| version.parse::<semver::Version>().is_ok())
+        {
+            self.warn_if_yanked(version).await?;
+        }
         self.invoke().await?;
         Ok(())
     }
 
-    pub async fn hash(&self) -> Result<xdr::Hash, Error> {
-        let res = self
+    /// Wasm name without its registry alias
+    fn published_name(&self) -> &str {
+        registries::split_ref(&self.wasm_name).1
+    }
+
+    async fn warn_if_yanked(&self, version: &str) -> Result<(), Error> {
+        let raw = self
             .config
             .invoke_registry(
-                &["fetch_hash", "--wasm_name", &self.wasm_name],
-                Some(&self.fee),
+                &[
+                    "fetch_yank_reason",
+                    "--wasm_name",
+                    self.published_name(),
+                    "--version",
+                    version,
+                ],
+                None,
                 true,
             )
             .await?;
-        let res = res.trim_matches('"');
-        Ok(res.parse().unwrap())
+        if let Some(reason) = serde_json::from_str::<Option<String>>(&raw)? {
+            eprintln!(
+                "⚠️ Version {version} of {:?} has been yanked: {reason}",
+                self.published_name()
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn hash(&self) -> Result<xdr::Hash, Error> {
+        let mut slop = vec!["fetch_hash", "--wasm_name", self.published_name()];
+        if let Some(version) = &self.version {
+            slop.extend(["--version", version]);
+        }
+        // A view call, so `--build-only` must not turn it into a transaction to export
+        let res = self.config.invoke_registry(&slop, None, true).await?;
+        Ok(res.trim_matches('"').parse()?)
     }
 
     pub async fn wasm(&self) -> Result<Vec<u8>, Error> {
@@ -107,12 +165,17 @@ impl Cmd {
 
     async fn invoke(&self) -> Result<(), Error> {
         let client = self.config.rpc_client()?;
-        let key = self.config.key_pair()?;
         let config = &self.config;
 
+        // Only the source account's public key is needed, so the transaction can be signed elsewhere
+        let source = config.source_account().await?;
+        let key = Uint256(match &source {
+            MuxedAccount::Ed25519(key) => key.0,
+            MuxedAccount::MuxedEd25519(muxed) => muxed.ed25519.0,
+        });
+
         // Get the account sequence number
-        let public_strkey =
-            stellar_strkey::ed25519::PublicKey(key.verifying_key().to_bytes()).to_string();
+        let public_strkey = stellar_strkey::ed25519::PublicKey(key.0).to_string();
 
         let contract_address = self.config.contract_sc_address()?;
         let contract_id = &self.config.contract_id()?;
@@ -162,17 +225,23 @@ impl Cmd {
             }
         };
 
+        // Auth entries of other signers are only signed when submitting, so an exported
+        // transaction could never land
+        if self.fee.build_only && !signers.is_empty() {
+            return Err(Error::BuildOnlyWithSigners(signers.len()));
+        }
+
         let invoke_contract_args = InvokeContractArgs {
             contract_address: contract_address.clone(),
             function_name: "deploy".try_into().unwrap(),
             args: [
-                ScVal::String(ScString(self.wasm_name.clone().try_into().unwrap())),
+                ScVal::String(ScString(self.published_name().try_into().unwrap())),
                 self.version.clone().map_or(ScVal::Void, |s| {
                     ScVal::String(ScString(s.try_into().unwrap()))
                 }),
                 ScVal::String(ScString(self.contract_name.clone().try_into().unwrap())),
                 ScVal::Address(xdr::ScAddress::Account(AccountId(
-                    xdr::PublicKey::PublicKeyTypeEd25519(Uint256(key.verifying_key().to_bytes())),
+                    xdr::PublicKey::PublicKeyTypeEd25519(key),
                 ))),
                 args,
             ]
@@ -182,9 +251,14 @@ impl Cmd {
 
         let account_details = client.get_account(&public_strkey).await?;
         let sequence: i64 = account_details.seq_num.into();
-        let tx = build_invoke_contract_tx(invoke_contract_args, sequence + 1, self.fee.fee, &key)?;
+        let tx =
+            build_invoke_contract_tx(invoke_contract_args, sequence + 1, self.fee.fee, source)?;
         let assembled = simulate_and_assemble_transaction(&client, &tx).await?;
         let mut txn = assembled.transaction().clone();
+        if self.fee.build_only {
+            println!("{}", unsigned_envelope(txn)?);
+            return Ok(());
+        }
         txn = config
             .sign_soroban_authorizations(&txn, &signers)
             .await?
@@ -203,7 +277,7 @@ fn build_invoke_contract_tx(
     parameters: InvokeContractArgs,
     sequence: i64,
     fee: u32,
-    key: &SigningKey,
+    source_account: MuxedAccount,
 ) -> Result<Transaction, Error> {
     let op = Operation {
         source_account: None,
@@ -213,7 +287,7 @@ fn build_invoke_contract_tx(
         }),
     };
     Ok(Transaction {
-        source_account: MuxedAccount::Ed25519(Uint256(key.verifying_key().to_bytes())),
+        source_account,
         fee,
         seq_num: SequenceNumber(sequence),
         cond: Preconditions::None,
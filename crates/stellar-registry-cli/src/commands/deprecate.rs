@@ -0,0 +1,53 @@
+use clap::Parser;
+
+use stellar_cli::{commands::contract::invoke, fee};
+
+use crate::{contract::NetworkContract, registries};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Name of published wasm
+    pub wasm_name: String,
+    /// Message shown to anyone deploying or installing the wasm, e.g. what to use instead
+    #[arg(required_unless_present = "undo")]
+    pub message: Option<String>,
+    /// Lift the deprecation
+    #[arg(long, conflicts_with = "message")]
+    pub undo: bool,
+    #[command(flatten)]
+    pub config: registries::Args,
+    #[command(flatten)]
+    pub fee: fee::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        // An empty message lifts the deprecation
+        let message = self.message.as_deref().unwrap_or_default();
+        self.config
+            .invoke_registry(
+                &[
+                    "deprecate",
+                    "--wasm_name",
+                    &self.wasm_name,
+                    "--message",
+                    message,
+                ],
+                Some(&self.fee),
+                false,
+            )
+            .await?;
+        if self.undo {
+            eprintln!("✅ {:?} is no longer deprecated", self.wasm_name);
+        } else {
+            eprintln!("✅ Deprecated {:?}", self.wasm_name);
+        }
+        Ok(())
+    }
+}
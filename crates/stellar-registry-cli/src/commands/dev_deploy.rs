@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use stellar_cli::{commands::contract::invoke, fee};
+
+use crate::{contract::NetworkContract, registries};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Name of deployed contract to upgrade
+    #[arg(long)]
+    pub contract_name: String,
+    /// Path to compiled wasm, which is uploaded without being published
+    #[arg(long)]
+    pub wasm: PathBuf,
+    /// Name of the contract's upgrade function, defaults to `upgrade`
+    #[arg(long)]
+    pub upgrade_fn: Option<String>,
+    #[command(flatten)]
+    pub config: registries::Args,
+    #[command(flatten)]
+    pub fee: fee::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Missing file argument {0:#?}")]
+    MissingFileArg(PathBuf),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        if !self.wasm.exists() {
+            return Err(Error::MissingFileArg(self.wasm.clone()));
+        }
+        let wasm = self.wasm.to_string_lossy();
+        let mut slop = vec![
+            "dev_deploy",
+            "--name",
+            &self.contract_name,
+            "--wasm-file-path",
+            &wasm,
+        ];
+        if let Some(upgrade_fn) = &self.upgrade_fn {
+            slop.extend(["--upgrade_fn", upgrade_fn]);
+        }
+        let contract_id: String = serde_json::from_str(
+            &self
+                .config
+                .invoke_registry(&slop, Some(&self.fee), false)
+                .await?,
+        )?;
+        eprintln!(
+            "✅ Upgraded {:?} to unpublished wasm {}",
+            self.contract_name,
+            self.wasm.display()
+        );
+        println!("{contract_id}");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    async fn rejects_missing_wasm() {
+        use super::*;
+        let cmd = Cmd::try_parse_from([
+            "dev-deploy",
+            "--contract-name",
+            "hello",
+            "--wasm",
+            "does/not/exist.wasm",
+        ])
+        .unwrap();
+        assert!(matches!(cmd.run().await, Err(Error::MissingFileArg(_))));
+    }
+
+    #[cfg(feature = "integration-tests")]
+    #[tokio::test]
+    async fn test_run() {
+        use std::env;
+        use stellar_scaffold_test::{find_registry_wasm, AssertExt, RegistryTest};
+        let registry = RegistryTest::new().await;
+
+        env::set_var("STELLAR_RPC_URL", "http://localhost:8000/soroban/rpc");
+        env::set_var("STELLAR_ACCOUNT", "alice");
+        env::set_var(
+            "STELLAR_NETWORK_PASSPHRASE",
+            "Standalone Network ; February 2017",
+        );
+        env::set_var("STELLAR_REGISTRY_CONTRACT_ID", &registry.registry_address);
+
+        let wasm_path = find_registry_wasm().unwrap();
+        registry
+            .registry_cli("publish")
+            .arg("--wasm")
+            .arg(&wasm_path)
+            .arg("--binver")
+            .arg("0.0.1")
+            .arg("--wasm-name")
+            .arg("upgradeable")
+            .assert()
+            .success();
+        registry
+            .registry_cli("deploy")
+            .arg("--contract-name")
+            .arg("upgradeable")
+            .arg("--wasm-name")
+            .arg("upgradeable")
+            .arg("--")
+            .arg("--admin")
+            .arg("alice")
+            .assert()
+            .success();
+
+        let contract_id = registry
+            .registry_cli("dev-deploy")
+            .arg("--contract-name")
+            .arg("upgradeable")
+            .arg("--wasm")
+            .arg(&wasm_path)
+            .arg("--upgrade-fn")
+            .arg("redeploy")
+            .assert()
+            .success()
+            .stdout_as_str();
+
+        // An unpublished wasm has no name or version to record
+        let info = registry
+            .registry_cli("info")
+            .arg("--contract")
+            .arg("upgradeable")
+            .assert()
+            .success()
+            .stdout_as_str();
+        assert!(
+            info.contains(&format!("contract_id: {}", contract_id.trim())),
+            "{info}"
+        );
+        assert!(!info.contains("version:"), "{info}");
+    }
+}
@@ -0,0 +1,292 @@
+use std::{fs, io, path::PathBuf};
+
+use clap::Parser;
+use serde_json::{json, Value};
+use soroban_rpc::{self as rpc, EventStart, EventType};
+use stellar_cli::{
+    config,
+    xdr::{self, Limits, ReadXdr, ScVal},
+};
+
+use crate::{contract::NetworkContract, registries};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// JSON file to write the index to. An existing index is updated from the last event it saw
+    #[arg(long, default_value = "registry-index.json")]
+    pub out_file: PathBuf,
+    /// Ledger to start reading events from when creating a new index. Must be within the RPC
+    /// server's event retention window
+    #[arg(long)]
+    pub start_ledger: Option<u32>,
+    /// Number of events to request from the RPC server at a time
+    #[arg(long, default_value_t = 100)]
+    pub page_size: usize,
+    #[command(flatten)]
+    pub config: registries::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Config(#[from] config::Error),
+    #[error(transparent)]
+    Rpc(#[from] rpc::Error),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+    #[error(transparent)]
+    Spec(#[from] soroban_spec_tools::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("--start-ledger is required when creating a new index")]
+    MissingStartLedger,
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        let mut index = self.load()?;
+        let client = self.config.rpc_client()?;
+        let contract_ids = [self.config.contract_id()?.to_string()];
+        let mut start = match (index["cursor"].as_str(), self.start_ledger) {
+            (Some(cursor), _) => EventStart::Cursor(cursor.to_string()),
+            (None, Some(ledger)) => EventStart::Ledger(ledger),
+            (None, None) => return Err(Error::MissingStartLedger),
+        };
+        let mut count = 0;
+        loop {
+            let response = client
+                .get_events(
+                    start,
+                    Some(EventType::Contract),
+                    &contract_ids,
+                    &[],
+                    Some(self.page_size),
+                )
+                .await?;
+            index["latest_ledger"] = response.latest_ledger.into();
+            let Some(last) = response.events.last() else {
+                break;
+            };
+            let cursor = last.id.clone();
+            for event in &response.events {
+                let (topic, data) = decode(event)?;
+                apply(&mut index, &topic, &data, event.ledger);
+            }
+            count += response.events.len();
+            index["cursor"] = cursor.clone().into();
+            if response.events.len() < self.page_size {
+                break;
+            }
+            start = EventStart::Cursor(cursor);
+        }
+        fs::write(&self.out_file, serde_json::to_string_pretty(&index)?)?;
+        eprintln!("✅ Indexed {count} events into {}", self.out_file.display());
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Value, Error> {
+        match fs::read_to_string(&self.out_file) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(json!({})),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Name and data of a registry event
+fn decode(event: &rpc::Event) -> Result<(String, Value), Error> {
+    let topic = match event.topic.first() {
+        Some(topic) => {
+            soroban_spec_tools::to_json(&ScVal::from_xdr_base64(topic, Limits::none())?)?
+        }
+        None => Value::Null,
+    };
+    let data = ScVal::from_xdr_base64(&event.value, Limits::none())?;
+    Ok((
+        topic.as_str().unwrap_or_default().to_string(),
+        soroban_spec_tools::to_json(&data)?,
+    ))
+}
+
+fn field<'a>(data: &'a Value, key: &str) -> &'a str {
+    data[key].as_str().unwrap_or_default()
+}
+
+fn push(list: &mut Value, item: &Value) {
+    if list.is_null() {
+        *list = json!([]);
+    }
+    if let Some(list) = list.as_array_mut() {
+        list.push(item.clone());
+    }
+}
+
+fn remove(list: &mut Value, item: &Value) {
+    if let Some(list) = list.as_array_mut() {
+        list.retain(|i| i != item);
+    }
+}
+
+/// Whether publishing `version` makes it the latest version. Prereleases never do
+fn is_newer(version: &str, latest: &Value) -> bool {
+    let Ok(version) = semver::Version::parse(version) else {
+        return latest.is_null();
+    };
+    version.pre.is_empty()
+        && latest
+            .as_str()
+            .and_then(|latest| semver::Version::parse(latest).ok())
+            .is_none_or(|latest| version > latest)
+}
+
+/// Update `index` with the state change a registry event describes
+fn apply(index: &mut Value, topic: &str, data: &Value, ledger: u32) {
+    apply_wasm(
+        &mut index["wasms"][field(data, "wasm_name")],
+        topic,
+        data,
+        ledger,
+    );
+    apply_contract(index, topic, data, ledger);
+    let namespace = &mut index["namespaces"][field(data, "namespace")];
+    match topic {
+        "claim" => *namespace = json!({ "owner": data["owner"], "members": [] }),
+        "add_membr" => push(&mut namespace["members"], &data["member"]),
+        "rm_membr" => remove(&mut namespace["members"], &data["member"]),
+        _ => {}
+    }
+    for key in ["wasms", "namespaces"] {
+        if let Some(map) = index[key].as_object_mut() {
+            map.retain(|_, value| !value.is_null());
+        }
+    }
+}
+
+fn apply_wasm(wasm: &mut Value, topic: &str, data: &Value, ledger: u32) {
+    let version = field(data, "version");
+    match topic {
+        "publish" => {
+            if wasm["author"].is_null() {
+                wasm["author"] = data["author"].clone();
+            }
+            wasm["versions"][version] = json!({
+                "hash": data["wasm_hash"],
+                "publisher": data["author"],
+                "ledger": ledger,
+            });
+            if is_newer(version, &wasm["latest"]) {
+                wasm["latest"] = version.into();
+            }
+        }
+        "latest" => wasm["latest"] = version.into(),
+        "yank" => wasm["versions"][version]["yanked"] = data["reason"].clone(),
+        "deprecate" => {
+            let message = field(data, "message");
+            wasm["deprecated"] = if message.is_empty() {
+                Value::Null
+            } else {
+                message.into()
+            };
+        }
+        "transfer" => wasm["author"] = data["new_author"].clone(),
+        "add_maint" => push(&mut wasm["maintainers"], &data["maintainer"]),
+        "rm_maint" => remove(&mut wasm["maintainers"], &data["maintainer"]),
+        _ => {}
+    }
+}
+
+fn apply_contract(index: &mut Value, topic: &str, data: &Value, ledger: u32) {
+    let name = field(data, "contract_name");
+    match topic {
+        "deploy" => {
+            let hash = index["wasms"][field(data, "wasm_name")]["versions"][field(data, "version")]
+                ["hash"]
+                .clone();
+            index["contracts"][name] = json!({
+                "contract_id": data["contract_id"],
+                "wasm_name": data["wasm_name"],
+                "version": data["version"],
+                "wasm_hash": hash,
+                "admin": data["deployer"],
+                "deployed_ledger": ledger,
+                "updated_ledger": ledger,
+            });
+        }
+        "register" => {
+            index["contracts"][name] = json!({
+                "contract_id": data["contract_id"],
+                "admin": data["admin"],
+                "deployed_ledger": ledger,
+                "updated_ledger": ledger,
+            });
+        }
+        "upgrade" | "repoint" => {
+            let contract = &mut index["contracts"][name];
+            for key in ["contract_id", "wasm_name", "version", "wasm_hash"] {
+                contract[key] = data[key].clone();
+            }
+            contract["updated_ledger"] = ledger.into();
+        }
+        "release" => {
+            if let Some(contracts) = index["contracts"].as_object_mut() {
+                contracts.remove(name);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::apply;
+
+    #[test]
+    fn rebuilds_registry_state() {
+        let mut index = json!({});
+        let publish = |version: &str| json!({ "wasm_name": "hello", "version": version, "author": "GA", "wasm_hash": version });
+        apply(&mut index, "publish", &publish("0.10.0"), 1);
+        apply(&mut index, "publish", &publish("0.9.0"), 2);
+        apply(&mut index, "publish", &publish("1.0.0-beta.1"), 2);
+        apply(
+            &mut index,
+            "yank",
+            &json!({ "wasm_name": "hello", "version": "0.9.0", "reason": "broken" }),
+            3,
+        );
+        apply(
+            &mut index,
+            "deploy",
+            &json!({
+                "wasm_name": "hello",
+                "contract_name": "hi",
+                "version": "0.10.0",
+                "deployer": "GA",
+                "contract_id": "CA",
+            }),
+            4,
+        );
+        apply(
+            &mut index,
+            "repoint",
+            &json!({ "contract_name": "hi", "admin": "GA", "contract_id": "CB" }),
+            5,
+        );
+        assert_eq!(index["wasms"]["hello"]["latest"], "0.10.0");
+        assert_eq!(
+            index["wasms"]["hello"]["versions"]["0.9.0"]["yanked"],
+            "broken"
+        );
+        assert_eq!(index["contracts"]["hi"]["contract_id"], "CB");
+        assert_eq!(index["contracts"]["hi"]["wasm_name"], json!(null));
+        assert_eq!(index["contracts"]["hi"]["deployed_ledger"], 4);
+        assert_eq!(index["contracts"]["hi"]["updated_ledger"], 5);
+        assert!(index["namespaces"].as_object().unwrap().is_empty());
+
+        apply(&mut index, "release", &json!({ "contract_name": "hi" }), 6);
+        assert!(index["contracts"].as_object().unwrap().is_empty());
+    }
+}
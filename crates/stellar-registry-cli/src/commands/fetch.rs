@@ -0,0 +1,178 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use sha2::{Digest, Sha256};
+
+pub use soroban_spec_tools::contract as contract_spec;
+use stellar_cli::{commands::contract::invoke, config, utils::rpc::get_remote_wasm_from_hash, xdr};
+
+use soroban_rpc as rpc;
+
+use crate::{contract::NetworkContract, registries};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Name of published wasm, optionally followed by `@` and an exact version or semver
+    /// requirement, e.g. `hello_world@1.2.0` or `@ahalabs/token@^1`
+    pub wasm: String,
+    /// Where to write the wasm, defaults to `<name>.wasm` in the current directory
+    #[arg(long, short = 'o')]
+    pub out_file: Option<PathBuf>,
+    /// Print the wasm's contract spec and metadata
+    #[arg(long)]
+    pub spec: bool,
+    #[command(flatten)]
+    pub config: registries::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Rpc(#[from] rpc::Error),
+    #[error(transparent)]
+    Config(#[from] config::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+    #[error("Cannot parse contract spec")]
+    CannotParseContractSpec,
+    #[error("Downloaded wasm has hash {actual}, but the registry records {expected}")]
+    HashMismatch {
+        expected: xdr::Hash,
+        actual: xdr::Hash,
+    },
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        let (wasm_name, version) = split_version(&self.wasm);
+        let mut slop = vec!["fetch_hash", "--wasm_name", wasm_name];
+        if let Some(version) = version {
+            slop.extend(["--version", version]);
+        }
+        let hash: String =
+            serde_json::from_str(&self.config.invoke_registry(&slop, None, true).await?)?;
+        let hash: xdr::Hash = hash.parse()?;
+        let wasm = get_remote_wasm_from_hash(&self.config.rpc_client()?, &hash).await?;
+        verify(&hash, &wasm)?;
+        if self.spec {
+            let spec =
+                contract_spec::Spec::new(&wasm).map_err(|_| Error::CannotParseContractSpec)?;
+            println!("{spec}");
+        }
+        let out_file = self.out_file.clone().unwrap_or_else(|| {
+            let name = wasm_name.rsplit('/').next().unwrap_or(wasm_name);
+            PathBuf::from(format!("{name}.wasm"))
+        });
+        if let Some(dir) = out_file.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&out_file, &wasm)?;
+        eprintln!("✅ Fetched {wasm_name} ({hash}) to {}", out_file.display());
+        Ok(())
+    }
+}
+
+/// Check that downloaded `wasm` is what the registry published under `expected`
+fn verify(expected: &xdr::Hash, wasm: &[u8]) -> Result<(), Error> {
+    let actual = xdr::Hash(Sha256::digest(wasm).into());
+    if actual != *expected {
+        return Err(Error::HashMismatch {
+            expected: expected.clone(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Split `name@version` into the name and version. Scoped names start with `@`, so only a later
+/// `@` separates the version
+fn split_version(wasm: &str) -> (&str, Option<&str>) {
+    match wasm.rsplit_once('@') {
+        Some((name, version)) if !name.is_empty() => (name, Some(version)),
+        _ => (wasm, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sha2::{Digest, Sha256};
+    use stellar_cli::xdr;
+
+    use super::{split_version, verify, Error};
+
+    #[test]
+    fn splits_version() {
+        assert_eq!(split_version("hello"), ("hello", None));
+        assert_eq!(split_version("hello@1.0.0"), ("hello", Some("1.0.0")));
+        assert_eq!(split_version("hello@^1"), ("hello", Some("^1")));
+        assert_eq!(split_version("@ahalabs/token"), ("@ahalabs/token", None));
+        assert_eq!(
+            split_version("@ahalabs/token@>=2, <3"),
+            ("@ahalabs/token", Some(">=2, <3"))
+        );
+    }
+
+    #[test]
+    fn rejects_wasm_with_another_hash() {
+        let wasm = b"\0asm published";
+        let hash = xdr::Hash(Sha256::digest(wasm).into());
+        assert!(verify(&hash, wasm).is_ok());
+        match verify(&hash, b"\0asm tampered") {
+            Err(Error::HashMismatch { expected, actual }) => {
+                assert_eq!(expected, hash);
+                assert_ne!(actual, hash);
+            }
+            res => panic!("expected a hash mismatch, got {res:?}"),
+        }
+    }
+
+    #[cfg(feature = "integration-tests")]
+    #[tokio::test]
+    async fn test_run() {
+        use std::env;
+        use stellar_scaffold_test::RegistryTest;
+        let registry = RegistryTest::new().await;
+        let test_env = registry.clone().env;
+
+        env::set_var("STELLAR_RPC_URL", "http://localhost:8000/soroban/rpc");
+        env::set_var("STELLAR_ACCOUNT", "alice");
+        env::set_var(
+            "STELLAR_NETWORK_PASSPHRASE",
+            "Standalone Network ; February 2017",
+        );
+        env::set_var("STELLAR_REGISTRY_CONTRACT_ID", &registry.registry_address);
+
+        let wasm_path = test_env
+            .cwd
+            .join("target/stellar/soroban_hello_world_contract.wasm");
+        registry
+            .registry_cli("publish")
+            .arg("--wasm")
+            .arg(&wasm_path)
+            .arg("--binver")
+            .arg("0.0.1")
+            .arg("--wasm-name")
+            .arg("hello")
+            .assert()
+            .success();
+
+        let out_file = test_env.cwd.join("fetched/hello.wasm");
+        registry
+            .registry_cli("fetch")
+            .arg("hello@0.0.1")
+            .arg("--out-file")
+            .arg(&out_file)
+            .assert()
+            .success();
+        assert_eq!(
+            std::fs::read(&out_file).unwrap(),
+            std::fs::read(&wasm_path).unwrap()
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use clap::{ArgGroup, Parser};
+use serde_json::Value;
+
+use stellar_cli::commands::contract::invoke;
+
+use crate::{
+    contract::{is_registry_error, NetworkContract, RegistryError},
+    registries,
+};
+
+/// Fields of the registry's `Metadata` record in the order they are printed
+const METADATA_FIELDS: [&str; 7] = [
+    "repository",
+    "homepage",
+    "license",
+    "description",
+    "scaffold_version",
+    "publisher",
+    "published_ledger",
+];
+
+/// Fields of the registry's `ContractInfo` record in the order they are printed
+const CONTRACT_INFO_FIELDS: [&str; 7] = [
+    "contract_id",
+    "wasm_name",
+    "version",
+    "wasm_hash",
+    "admin",
+    "deployed_ledger",
+    "updated_ledger",
+];
+
+#[derive(Parser, Debug, Clone)]
+#[command(group(ArgGroup::new("target").required(true).args(["wasm_name", "contract"])))]
+pub struct Cmd {
+    /// Name of published wasm
+    pub wasm_name: Option<String>,
+    /// Version or semver requirement to show, defaults to the latest version
+    #[arg(long, conflicts_with = "contract")]
+    pub version: Option<String>,
+    /// Show what a deployed contract runs instead of a published wasm
+    #[arg(long)]
+    pub contract: Option<String>,
+    #[command(flatten)]
+    pub config: registries::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Pass a wasm name or --contract")]
+    MissingName,
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        match (&self.wasm_name, &self.contract) {
+            (_, Some(contract_name)) => self.contract_info(contract_name).await,
+            (Some(wasm_name), None) => self.wasm_info(wasm_name).await,
+            (None, None) => Err(Error::MissingName),
+        }
+    }
+
+    async fn contract_info(&self, contract_name: &str) -> Result<(), Error> {
+        let raw = self
+            .config
+            .invoke_registry(
+                &["fetch_contract_info", "--contract_name", contract_name],
+                None,
+                true,
+            )
+            .await?;
+        let info: Value = serde_json::from_str(&raw)?;
+        println!("name: {contract_name}");
+        for field in CONTRACT_INFO_FIELDS {
+            print_field(field, &info[field]);
+        }
+        Ok(())
+    }
+
+    async fn wasm_info(&self, wasm_name: &str) -> Result<(), Error> {
+        let hash = self.invoke("fetch_hash", wasm_name).await?;
+        println!("name: {wasm_name}");
+        // Versions published before the registry recorded metadata only have a hash
+        match self.invoke("get_metadata", wasm_name).await {
+            Ok(metadata) => {
+                let metadata: Value = serde_json::from_str(&metadata)?;
+                print_field("version", &metadata["version"]);
+                println!("hash: {}", hash.trim_matches('"'));
+                for field in METADATA_FIELDS {
+                    print_field(field, &metadata[field]);
+                }
+            }
+            Err(e) if is_registry_error(&e, RegistryError::NoSuchMetadata) => {
+                println!("hash: {}", hash.trim_matches('"'));
+            }
+            Err(e) => return Err(e.into()),
+        }
+        let deprecation = self
+            .config
+            .invoke_registry(&["fetch_deprecation", "--wasm_name", wasm_name], None, true)
+            .await?;
+        print_field("deprecated", &serde_json::from_str(&deprecation)?);
+        Ok(())
+    }
+
+    async fn invoke(&self, fn_name: &str, wasm_name: &str) -> Result<String, invoke::Error> {
+        let mut slop = vec![fn_name, "--wasm_name", wasm_name];
+        if let Some(version) = &self.version {
+            slop.extend(["--version", version]);
+        }
+        self.config.invoke_registry(&slop, None, true).await
+    }
+}
+
+fn print_field(name: &str, value: &Value) {
+    match value {
+        Value::Null => {}
+        Value::String(s) => println!("{name}: {s}"),
+        value => println!("{name}: {value}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::Cmd;
+
+    #[test]
+    fn requires_a_wasm_name_or_contract() {
+        let parse = |args: &[&str]| Cmd::try_parse_from([&["info"], args].concat());
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["hello", "--contract", "hi"]).is_err());
+        assert!(parse(&["hello"]).is_ok());
+        assert!(parse(&["--contract", "hi"]).is_ok());
+    }
+}
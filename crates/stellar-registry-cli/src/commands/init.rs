@@ -0,0 +1,144 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use stellar_cli::{
+    commands::{contract::deploy, NetworkRunnable},
+    config, fee,
+};
+
+use crate::{registries::Registries, CommandParser};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Path to the registry contract's compiled wasm
+    #[arg(long, default_value = "target/stellar/registry.wasm")]
+    pub wasm: PathBuf,
+    /// Admin of the new registry, if not provided, the source account will be used
+    #[arg(long)]
+    pub admin: Option<String>,
+    /// Name to save the registry under in config, usable with `--registry`
+    #[arg(long, default_value = "registry")]
+    pub alias: String,
+    /// Use the new registry by default on this network
+    #[arg(long)]
+    pub default: bool,
+    #[command(flatten)]
+    pub config: config::Args,
+    #[command(flatten)]
+    pub fee: fee::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Deploy(#[from] deploy::wasm::Error),
+    #[error(transparent)]
+    Config(#[from] config::Error),
+    #[error(transparent)]
+    Clap(#[from] clap::Error),
+    #[error(transparent)]
+    LocatorConfig(#[from] config::locator::Error),
+    #[error("Missing file argument {0:#?}")]
+    MissingFileArg(PathBuf),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        if !self.wasm.exists() {
+            return Err(Error::MissingFileArg(self.wasm.clone()));
+        }
+        let admin = if let Some(admin) = self.admin.clone() {
+            admin
+        } else {
+            self.config.source_account().await?.to_string()
+        };
+        let wasm = self.wasm.to_string_lossy();
+        let mut cmd = deploy::wasm::Cmd::parse_arg_vec(&[
+            "--wasm",
+            &wasm,
+            "--alias",
+            &self.alias,
+            "--",
+            "--admin",
+            &admin,
+        ])?;
+        cmd.config = self.config.clone();
+        cmd.fee = self.fee.clone();
+        let contract_id = cmd
+            .run_against_rpc_server(None, None)
+            .await?
+            .into_result()
+            .expect("no contract id returned by 'contract deploy'");
+
+        let mut registries = Registries::load(&self.config.locator)?;
+        registries
+            .registries
+            .insert(self.alias.clone(), contract_id.to_string());
+        if self.default {
+            let network = self.config.get_network()?;
+            registries
+                .default
+                .insert(network.network_passphrase, self.alias.clone());
+        }
+        registries.save(&self.config.locator)?;
+
+        eprintln!("✅ Deployed registry {:?} with admin {admin}", self.alias);
+        if !self.default {
+            eprintln!("Use it by passing --registry {}", self.alias);
+        }
+        println!("{contract_id}");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "integration-tests")]
+    #[tokio::test]
+    async fn test_run() {
+        use stellar_cli::config::locator;
+        use stellar_scaffold_test::{find_registry_wasm, AssertExt, RegistryTest};
+
+        use crate::registries::Registries;
+        let registry = RegistryTest::new().await;
+        let test_env = registry.clone().env;
+        let config_dir = test_env.cwd.to_str().unwrap();
+
+        // Without a network name, so the default is keyed on the passphrase
+        let contract_id = registry
+            .registry_cli("init")
+            .arg("--wasm")
+            .arg(find_registry_wasm().unwrap())
+            .arg("--alias")
+            .arg("org")
+            .arg("--default")
+            .arg("--rpc-url")
+            .arg("http://localhost:8000/soroban/rpc")
+            .arg("--network-passphrase")
+            .arg("Standalone Network ; February 2017")
+            .arg("--source-account")
+            .arg("alice")
+            .arg("--config-dir")
+            .arg(config_dir)
+            .assert()
+            .success()
+            .stdout_as_str();
+
+        let registries = Registries::load(&locator::Args {
+            global: false,
+            config_dir: Some(config_dir.into()),
+        })
+        .unwrap();
+        assert_eq!(
+            registries.registries.get("org").map(String::as_str),
+            Some(contract_id.trim())
+        );
+        assert_eq!(
+            registries
+                .default_for("Standalone Network ; February 2017")
+                .as_deref(),
+            Some(contract_id.trim())
+        );
+    }
+}
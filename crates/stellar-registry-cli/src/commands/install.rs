@@ -6,15 +6,19 @@ use stellar_cli::{
 };
 use stellar_strkey::Contract;
 
-use crate::contract::NetworkContract;
+use crate::{contract::NetworkContract, deprecation, registries};
 
 #[derive(Parser, Debug, Clone)]
 pub struct Cmd {
-    /// Name of deployed contract
+    /// Name of deployed contract, either plain like `token` or scoped like `@ahalabs/token`.
+    /// Prefix it with a registry's alias, e.g. `org:token`, to install from that registry
     pub contract_name: String,
+    /// Local alias for the contract, defaults to the contract name without its namespace
+    #[arg(long)]
+    pub alias: Option<String>,
 
     #[command(flatten)]
-    pub config: config::Args,
+    pub config: registries::Args,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -31,38 +35,65 @@ pub enum Error {
     LocatorConfig(#[from] stellar_cli::config::locator::Error),
     #[error(transparent)]
     Config(#[from] stellar_cli::config::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Deprecation(#[from] deprecation::Error),
+    #[error(transparent)]
+    Registries(#[from] registries::Error),
 }
 
 impl Cmd {
     pub async fn run(&self) -> Result<(), Error> {
+        let cmd = Self {
+            config: self.config.for_ref(&self.contract_name)?,
+            ..self.clone()
+        };
+        cmd.install().await
+    }
+
+    async fn install(&self) -> Result<(), Error> {
         // Use the network config from flattened args
         let network = self.config.get_network()?;
         let network_passphrase = network.network_passphrase;
 
         let contract = self.get_contract_id().await?;
+        self.warn_if_deprecated().await?;
 
         // Only create alias mapping, don't fetch wasm here
-        self.config.locator.save_contract_id(
-            &network_passphrase,
-            &contract,
-            &self.contract_name,
-        )?;
-
-        eprintln!(
-            "✅ Successfully registered contract alias '{}'",
-            self.contract_name
-        );
+        let alias = self.alias();
+        self.config
+            .locator
+            .save_contract_id(&network_passphrase, &contract, alias)?;
+
+        eprintln!("✅ Successfully registered contract alias '{alias}'");
         eprintln!("Contract ID: {:?}", contract.to_string());
 
         Ok(())
     }
 
+    /// Alias to save the contract under. Aliases cannot contain `@` or `/`, so a scoped name such
+    /// as `@ahalabs/token` is saved as `token`
+    pub fn alias(&self) -> &str {
+        self.alias.as_deref().unwrap_or_else(|| {
+            let name = self.name();
+            name.strip_prefix('@')
+                .and_then(|scoped| scoped.split_once('/'))
+                .map_or(name, |(_, name)| name)
+        })
+    }
+
+    /// Contract name without its registry alias
+    fn name(&self) -> &str {
+        registries::split_ref(&self.contract_name).1
+    }
+
     pub async fn get_contract_id(&self) -> Result<Contract, Error> {
-        if self.contract_name == "registry" {
+        if self.name() == "registry" {
             return Ok(self.config.contract_id()?);
         }
         // Prepare the arguments for invoke_registry
-        let slop = vec!["fetch_contract_id", "--contract-name", &self.contract_name];
+        let slop = vec!["fetch_contract_id", "--contract-name", self.name()];
         // Use this.config directly
         eprintln!("Fetching contract ID via registry...");
         let raw = self.config.invoke_registry(&slop, None, true).await?;
@@ -70,10 +101,32 @@ impl Cmd {
         let contract_id = raw.trim_matches('"').to_string();
         Ok(contract_id.parse()?)
     }
+
+    async fn warn_if_deprecated(&self) -> Result<(), Error> {
+        if self.name() == "registry" {
+            return Ok(());
+        }
+        deprecation::warn_if_contract_deprecated(&self.config, self.name()).await?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    #[test]
+    fn scoped_names_alias_to_name() {
+        use super::Cmd;
+        use crate::CommandParser;
+        let alias = |args: &[&str]| Cmd::parse_arg_vec(args).unwrap().alias().to_string();
+        assert_eq!(alias(&["token"]), "token");
+        assert_eq!(alias(&["@ahalabs/token"]), "token");
+        assert_eq!(alias(&["org:@ahalabs/token"]), "token");
+        assert_eq!(
+            alias(&["@ahalabs/token", "--alias", "aha-token"]),
+            "aha-token"
+        );
+    }
+
     #[cfg(feature = "integration-tests")]
     #[tokio::test]
     async fn test_run() {
@@ -127,14 +180,18 @@ mod tests {
         // Create test command for install
         let cmd = Cmd {
             contract_name: "hello".to_owned(),
-            config: config::Args {
-                locator: locator::Args {
-                    global: false,
-                    config_dir: Some(test_env.cwd.to_str().unwrap().into()),
-                },
-                network: network::Args {
-                    rpc_url: Some("http://localhost:8000/soroban/rpc".to_string()),
-                    network_passphrase: Some("Standalone Network ; February 2017".to_string()),
+            alias: None,
+            config: registries::Args {
+                config: config::Args {
+                    locator: locator::Args {
+                        global: false,
+                        config_dir: Some(test_env.cwd.to_str().unwrap().into()),
+                    },
+                    network: network::Args {
+                        rpc_url: Some("http://localhost:8000/soroban/rpc".to_string()),
+                        network_passphrase: Some("Standalone Network ; February 2017".to_string()),
+                        ..Default::default()
+                    },
                     ..Default::default()
                 },
                 ..Default::default()
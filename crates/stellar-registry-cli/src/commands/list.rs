@@ -0,0 +1,70 @@
+use clap::Parser;
+
+use stellar_cli::commands::contract::invoke;
+
+use crate::{contract::NetworkContract, registries};
+
+/// Most names the registry returns from a single `list_wasms` call
+const PAGE_SIZE: u32 = 100;
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Number of names to skip, in the order they were first published
+    #[arg(long, default_value_t = 0)]
+    pub cursor: u32,
+    /// Maximum number of names to list, if not provided all names are listed
+    #[arg(long)]
+    pub limit: Option<u32>,
+    #[command(flatten)]
+    pub config: registries::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        for name in self.wasm_names().await? {
+            println!("{name}");
+        }
+        Ok(())
+    }
+
+    /// Fetch published wasm names page by page until the registry has no more or `limit` is reached
+    pub async fn wasm_names(&self) -> Result<Vec<String>, Error> {
+        let mut names = Vec::new();
+        let mut cursor = self.cursor;
+        let mut remaining = self.limit.unwrap_or(u32::MAX);
+        while remaining > 0 {
+            let page_size = remaining.min(PAGE_SIZE);
+            let page = self.page(cursor, page_size).await?;
+            let full = page.len() == page_size as usize;
+            names.extend(page);
+            if !full {
+                break;
+            }
+            cursor += page_size;
+            remaining -= page_size;
+        }
+        Ok(names)
+    }
+
+    async fn page(&self, cursor: u32, limit: u32) -> Result<Vec<String>, Error> {
+        let cursor = cursor.to_string();
+        let limit = limit.to_string();
+        let raw = self
+            .config
+            .invoke_registry(
+                &["list_wasms", "--cursor", &cursor, "--limit", &limit],
+                None,
+                true,
+            )
+            .await?;
+        Ok(serde_json::from_str(&raw)?)
+    }
+}
@@ -2,10 +2,28 @@ use std::str::FromStr;
 
 use clap::{command, CommandFactory, FromArgMatches, Parser};
 
+pub mod add_maintainer;
 pub mod deploy;
+pub mod deprecate;
+pub mod dev_deploy;
+pub mod events;
+pub mod fetch;
+pub mod info;
+pub mod init;
 pub mod install;
+pub mod list;
+pub mod namespace;
 pub mod publish;
+pub mod register_existing;
+pub mod release;
+pub mod remove_maintainer;
+pub mod repoint;
+pub mod submit;
+pub mod transfer_author;
+pub mod upgrade;
 pub mod version;
+pub mod versions;
+pub mod yank;
 
 const ABOUT: &str = "Add, manage, and use Wasm packages & named contracts in the Stellar Registry";
 
@@ -37,10 +55,28 @@ impl Root {
     }
     pub async fn run(&mut self) -> Result<(), Error> {
         match &mut self.cmd {
+            Cmd::Init(init) => init.run().await?,
             Cmd::Publish(p) => p.run().await?,
             Cmd::Version(p) => p.run(),
             Cmd::Install(i) => i.run().await?,
+            Cmd::Fetch(fetch) => fetch.run().await?,
             Cmd::Deploy(deploy) => deploy.run().await?,
+            Cmd::Upgrade(upgrade) => upgrade.run().await?,
+            Cmd::DevDeploy(dev_deploy) => dev_deploy.run().await?,
+            Cmd::List(list) => list.run().await?,
+            Cmd::Info(info) => info.run().await?,
+            Cmd::Versions(versions) => versions.run().await?,
+            Cmd::Yank(yank) => yank.run().await?,
+            Cmd::Deprecate(deprecate) => deprecate.run().await?,
+            Cmd::TransferAuthor(transfer) => transfer.run().await?,
+            Cmd::AddMaintainer(add) => add.run().await?,
+            Cmd::RemoveMaintainer(remove) => remove.run().await?,
+            Cmd::Namespace(namespace) => namespace.run().await?,
+            Cmd::RegisterExisting(register) => register.run().await?,
+            Cmd::Repoint(repoint) => repoint.run().await?,
+            Cmd::Release(release) => release.run().await?,
+            Cmd::Events(events) => events.run().await?,
+            Cmd::Submit(submit) => submit.run().await?,
         }
         Ok(())
     }
@@ -56,22 +92,95 @@ impl FromStr for Root {
 
 #[derive(Parser, Debug)]
 pub enum Cmd {
+    /// Deploy a new registry, e.g. a private registry or one on a local network
+    Init(Box<init::Cmd>),
     /// Publish Wasm to registry with package name and semantic version
     Publish(Box<publish::Cmd>),
     /// Deploy a named contract from a published Wasm
     Deploy(Box<deploy::Cmd>),
+    /// Upgrade a named contract to a published Wasm
+    Upgrade(Box<upgrade::Cmd>),
+    /// Upgrade a named contract to a local Wasm file without publishing it
+    DevDeploy(Box<dev_deploy::Cmd>),
     /// Create a local `stellar contract alias` from a named registry contract
     Install(Box<install::Cmd>),
+    /// Download a published Wasm and verify it against the registry's hash
+    Fetch(Box<fetch::Cmd>),
+    /// List the names of published Wasm binaries
+    List(Box<list::Cmd>),
+    /// List the published versions of a Wasm binary
+    Versions(Box<versions::Cmd>),
+    /// Show the metadata of a published Wasm binary
+    Info(Box<info::Cmd>),
+    /// Yank a published version so it is skipped when resolving the latest version
+    Yank(Box<yank::Cmd>),
+    /// Mark a published Wasm name as deprecated
+    Deprecate(Box<deprecate::Cmd>),
+    /// Transfer a published Wasm name to a new author
+    TransferAuthor(Box<transfer_author::Cmd>),
+    /// Allow another address to publish new versions of a Wasm name
+    AddMaintainer(Box<add_maintainer::Cmd>),
+    /// Revoke a maintainer's permission to publish new versions of a Wasm name
+    RemoveMaintainer(Box<remove_maintainer::Cmd>),
+    /// Claim and manage namespaces for scoped names such as `@ahalabs/token`
+    #[command(subcommand)]
+    Namespace(Box<namespace::Cmd>),
+    /// Name a contract that was deployed outside the registry
+    RegisterExisting(Box<register_existing::Cmd>),
+    /// Point a contract name at a different contract address
+    Repoint(Box<repoint::Cmd>),
+    /// Release a contract name so it can be deployed or registered again
+    Release(Box<release::Cmd>),
+    /// Build a local JSON index of the registry's state from its events
+    Events(Box<events::Cmd>),
+    /// Submit a transaction built with `--build-only` once it has been signed
+    Submit(Box<submit::Cmd>),
     /// Version of the scaffold-registry-cli
     Version(version::Cmd),
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
+    #[error(transparent)]
+    Init(#[from] init::Error),
     #[error(transparent)]
     Publish(#[from] publish::Error),
     #[error(transparent)]
     Deploy(#[from] deploy::Error),
     #[error(transparent)]
+    Upgrade(#[from] upgrade::Error),
+    #[error(transparent)]
+    DevDeploy(#[from] dev_deploy::Error),
+    #[error(transparent)]
     Install(#[from] install::Error),
+    #[error(transparent)]
+    Fetch(#[from] fetch::Error),
+    #[error(transparent)]
+    List(#[from] list::Error),
+    #[error(transparent)]
+    Versions(#[from] versions::Error),
+    #[error(transparent)]
+    Info(#[from] info::Error),
+    #[error(transparent)]
+    Yank(#[from] yank::Error),
+    #[error(transparent)]
+    Deprecate(#[from] deprecate::Error),
+    #[error(transparent)]
+    TransferAuthor(#[from] transfer_author::Error),
+    #[error(transparent)]
+    AddMaintainer(#[from] add_maintainer::Error),
+    #[error(transparent)]
+    RemoveMaintainer(#[from] remove_maintainer::Error),
+    #[error(transparent)]
+    Namespace(#[from] namespace::Error),
+    #[error(transparent)]
+    RegisterExisting(#[from] register_existing::Error),
+    #[error(transparent)]
+    Repoint(#[from] repoint::Error),
+    #[error(transparent)]
+    Release(#[from] release::Error),
+    #[error(transparent)]
+    Events(#[from] events::Error),
+    #[error(transparent)]
+    Submit(#[from] submit::Error),
 }
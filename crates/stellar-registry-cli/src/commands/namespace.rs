@@ -0,0 +1,116 @@
+use clap::{Parser, Subcommand};
+
+use stellar_cli::{commands::contract::invoke, config, fee};
+
+use crate::{contract::NetworkContract, registries};
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Cmd {
+    /// Claim a namespace so only you and its members can publish and deploy `@namespace/name`
+    Claim(Claim),
+    /// Allow another address to publish and deploy names in the namespace
+    AddMember(Member),
+    /// Revoke a member's access to the namespace
+    RemoveMember(Member),
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct Claim {
+    /// Namespace to claim, without the `@`
+    pub namespace: String,
+    /// Owner of the namespace, if not provided, the source account will be used
+    #[arg(long)]
+    pub owner: Option<String>,
+    #[command(flatten)]
+    pub config: registries::Args,
+    #[command(flatten)]
+    pub fee: fee::Args,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct Member {
+    /// Namespace, without the `@`
+    pub namespace: String,
+    /// Address or key name of the member
+    pub member: String,
+    #[command(flatten)]
+    pub config: registries::Args,
+    #[command(flatten)]
+    pub fee: fee::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+    #[error(transparent)]
+    Config(#[from] config::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        match self {
+            Cmd::Claim(claim) => claim.run().await,
+            Cmd::AddMember(member) => {
+                member.run("add_namespace_member").await?;
+                eprintln!(
+                    "✅ Added {} as a member of @{}",
+                    member.member, member.namespace
+                );
+                Ok(())
+            }
+            Cmd::RemoveMember(member) => {
+                member.run("remove_namespace_member").await?;
+                eprintln!(
+                    "✅ Removed {} as a member of @{}",
+                    member.member, member.namespace
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Claim {
+    pub async fn run(&self) -> Result<(), Error> {
+        let owner = if let Some(owner) = self.owner.clone() {
+            owner
+        } else {
+            self.config.source_account().await?.to_string()
+        };
+        self.config
+            .invoke_registry(
+                &[
+                    "claim_namespace",
+                    "--namespace",
+                    &self.namespace,
+                    "--owner",
+                    &owner,
+                ],
+                Some(&self.fee),
+                false,
+            )
+            .await?;
+        eprintln!("✅ Claimed @{}", self.namespace);
+        Ok(())
+    }
+}
+
+impl Member {
+    async fn run(&self, fn_name: &str) -> Result<(), Error> {
+        self.config
+            .invoke_registry(
+                &[
+                    fn_name,
+                    "--namespace",
+                    &self.namespace,
+                    "--member",
+                    &self.member,
+                ],
+                Some(&self.fee),
+                false,
+            )
+            .await?;
+        Ok(())
+    }
+}
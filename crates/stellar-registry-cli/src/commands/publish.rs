@@ -9,7 +9,7 @@ use stellar_cli::{
     xdr::{ScMetaEntry, ScMetaV0},
 };
 
-use crate::contract::NetworkContract;
+use crate::{contract::NetworkContract, registries};
 
 #[derive(Parser, Debug, Clone)]
 pub struct Cmd {
@@ -32,11 +32,20 @@ pub struct Cmd {
     #[arg(last = true, id = "CONTRACT_FN_AND_ARGS")]
     pub slop: Vec<OsString>,
     #[command(flatten)]
-    pub config: config::Args,
+    pub config: registries::Args,
     #[command(flatten)]
     pub fee: fee::Args,
 }
 
+/// Contract meta keys recorded by the registry, mapped to their `PublishMetadata` field
+const METADATA_KEYS: [(&str, &str); 5] = [
+    ("source_repo", "repository"),
+    ("home_domain", "homepage"),
+    ("license", "license"),
+    ("description", "description"),
+    ("scaffold_version", "scaffold_version"),
+];
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error(transparent)]
@@ -59,17 +68,17 @@ impl Cmd {
         let wasm_bytes =
             std::fs::read(&self.wasm).map_err(|_| Error::MissingFileArg(self.wasm.clone()))?;
         let spec =
-            contract_spec::Spec::new(&wasm_bytes).map_err(|_| Error::CannotParseContractSpec);
+            contract_spec::Spec::new(&wasm_bytes).map_err(|_| Error::CannotParseContractSpec)?;
 
         // Prepare a mutable vector for the base arguments
         let mut args = vec![
-            "publish".to_string(),
+            "publish_with_metadata".to_string(),
             "--wasm-file-path".to_string(),
             self.wasm.to_string_lossy().to_string(),
         ];
 
         // Use `filter_map` to extract relevant metadata and format as arguments
-        args.extend(spec?.meta.iter().filter_map(|entry| match entry {
+        args.extend(spec.meta.iter().filter_map(|entry| match entry {
             ScMetaEntry::ScMetaV0(ScMetaV0 { key, val }) => {
                 let key_str = key.to_string();
                 match key_str.as_str() {
@@ -83,6 +92,8 @@ impl Cmd {
             }
         }));
 
+        args.push(format!("--metadata={}", publish_metadata(&spec.meta)));
+
         // Add wasm_name if specified
         if let Some(ref wasm_name) = self.wasm_name {
             args.push(format!("--wasm_name={wasm_name}"));
@@ -102,13 +113,18 @@ impl Cmd {
         args.push(format!("--author={author}"));
 
         // Pass config and fee to invoke_registry
-        self.config
+        let res = self
+            .config
             .invoke_registry(
                 &args.iter().map(String::as_str).collect::<Vec<_>>(),
                 Some(&self.fee),
                 self.dry_run,
             )
             .await?;
+        if self.fee.build_only {
+            println!("{res}");
+            return Ok(());
+        }
         eprintln!(
             "{}Succesfully published {args:?}",
             if self.dry_run { "Dry Run: " } else { "" }
@@ -116,3 +132,50 @@ impl Cmd {
         Ok(())
     }
 }
+
+/// JSON `PublishMetadata` argument built from the wasm's contract meta
+fn publish_metadata(meta: &[ScMetaEntry]) -> serde_json::Value {
+    let value = |key: &str| {
+        meta.iter().find_map(|entry| match entry {
+            ScMetaEntry::ScMetaV0(ScMetaV0 { key: k, val }) => {
+                (k.to_string() == key).then(|| val.to_string())
+            }
+        })
+    };
+    METADATA_KEYS
+        .iter()
+        .map(|(key, field)| ((*field).to_string(), value(key).into()))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(key: &str, val: &str) -> ScMetaEntry {
+        ScMetaEntry::ScMetaV0(ScMetaV0 {
+            key: key.try_into().unwrap(),
+            val: val.try_into().unwrap(),
+        })
+    }
+
+    #[test]
+    fn metadata_from_contract_meta() {
+        let metadata = publish_metadata(&[
+            meta("name", "token"),
+            meta("source_repo", "https://github.com/ahalabs/scaffold-stellar"),
+            meta("scaffold_version", "0.0.10"),
+        ]);
+        assert_eq!(
+            metadata,
+            serde_json::json!({
+                "repository": "https://github.com/ahalabs/scaffold-stellar",
+                "homepage": null,
+                "license": null,
+                "description": null,
+                "scaffold_version": "0.0.10",
+            })
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use clap::Parser;
+
+use stellar_cli::{commands::contract::invoke, config, fee};
+
+use crate::{contract::NetworkContract, registries};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Name to give the contract
+    pub contract_name: String,
+    /// Address of a contract deployed outside the registry
+    pub contract_id: String,
+    /// Admin who can repoint and release the name, if not provided, the source account will be used
+    #[arg(long)]
+    pub admin: Option<String>,
+    #[command(flatten)]
+    pub config: registries::Args,
+    #[command(flatten)]
+    pub fee: fee::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+    #[error(transparent)]
+    Config(#[from] config::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        let admin = if let Some(admin) = self.admin.clone() {
+            admin
+        } else {
+            self.config.source_account().await?.to_string()
+        };
+        self.config
+            .invoke_registry(
+                &[
+                    "register_existing",
+                    "--contract_name",
+                    &self.contract_name,
+                    "--contract_id",
+                    &self.contract_id,
+                    "--admin",
+                    &admin,
+                ],
+                Some(&self.fee),
+                false,
+            )
+            .await?;
+        eprintln!(
+            "✅ Registered {} as {:?}",
+            self.contract_id, self.contract_name
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::Cmd;
+
+    #[test]
+    fn admin_defaults_to_source_account() {
+        let parse = |args: &[&str]| Cmd::try_parse_from([&["register-existing"], args].concat());
+        assert!(parse(&["token"]).is_err());
+        let cmd = parse(&["token", "CCONTRACT"]).unwrap();
+        assert_eq!(cmd.admin, None);
+        let cmd = parse(&["token", "CCONTRACT", "--admin", "alice"]).unwrap();
+        assert_eq!(cmd.admin.as_deref(), Some("alice"));
+    }
+}
@@ -0,0 +1,130 @@
+use clap::Parser;
+
+use stellar_cli::{commands::contract::invoke, fee};
+
+use crate::{contract::NetworkContract, registries};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Name of deployed contract to release
+    pub contract_name: String,
+    #[command(flatten)]
+    pub config: registries::Args,
+    #[command(flatten)]
+    pub fee: fee::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        self.config
+            .invoke_registry(
+                &["release", "--contract_name", &self.contract_name],
+                Some(&self.fee),
+                false,
+            )
+            .await?;
+        eprintln!("✅ Released {:?}", self.contract_name);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "integration-tests")]
+    #[tokio::test]
+    async fn test_run() {
+        use std::env;
+        use stellar_scaffold_test::{AssertExt, RegistryTest};
+        let registry = RegistryTest::new().await;
+        let test_env = registry.clone().env;
+
+        env::set_var("STELLAR_RPC_URL", "http://localhost:8000/soroban/rpc");
+        env::set_var("STELLAR_ACCOUNT", "alice");
+        env::set_var(
+            "STELLAR_NETWORK_PASSPHRASE",
+            "Standalone Network ; February 2017",
+        );
+        env::set_var("STELLAR_REGISTRY_CONTRACT_ID", &registry.registry_address);
+
+        let contract_id = |name: &str| {
+            registry
+                .registry_cli("info")
+                .arg("--contract")
+                .arg(name)
+                .assert()
+                .success()
+                .stdout_as_str()
+                .lines()
+                .find_map(|line| line.strip_prefix("contract_id: "))
+                .expect("info prints the contract id")
+                .to_string()
+        };
+
+        // Deploy a registry contract to have a second address to repoint to
+        let wasm_path = test_env
+            .cwd
+            .join("target/stellar/soroban_hello_world_contract.wasm");
+        registry
+            .registry_cli("publish")
+            .arg("--wasm")
+            .arg(&wasm_path)
+            .arg("--binver")
+            .arg("0.0.2")
+            .arg("--wasm-name")
+            .arg("hello")
+            .assert()
+            .success();
+        registry
+            .registry_cli("deploy")
+            .arg("--contract-name")
+            .arg("hello")
+            .arg("--wasm-name")
+            .arg("hello")
+            .assert()
+            .success();
+        let hello_id = contract_id("hello");
+
+        // The registry itself was deployed outside the registry
+        registry
+            .registry_cli("register-existing")
+            .arg("existing")
+            .arg(&registry.registry_address)
+            .assert()
+            .success();
+        assert_eq!(contract_id("existing"), registry.registry_address);
+
+        registry
+            .registry_cli("repoint")
+            .arg("existing")
+            .arg(&hello_id)
+            .assert()
+            .success();
+        assert_eq!(contract_id("existing"), hello_id);
+
+        registry
+            .registry_cli("release")
+            .arg("existing")
+            .assert()
+            .success();
+        registry
+            .registry_cli("info")
+            .arg("--contract")
+            .arg("existing")
+            .assert()
+            .failure();
+
+        // A released name can be registered again
+        registry
+            .registry_cli("register-existing")
+            .arg("existing")
+            .arg(&registry.registry_address)
+            .assert()
+            .success();
+    }
+}
@@ -0,0 +1,62 @@
+use clap::Parser;
+
+use stellar_cli::{commands::contract::invoke, fee};
+
+use crate::{contract::NetworkContract, registries};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Name of deployed contract
+    pub contract_name: String,
+    /// Address of the contract the name should now point to
+    pub contract_id: String,
+    #[command(flatten)]
+    pub config: registries::Args,
+    #[command(flatten)]
+    pub fee: fee::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        self.config
+            .invoke_registry(
+                &[
+                    "repoint",
+                    "--contract_name",
+                    &self.contract_name,
+                    "--contract_id",
+                    &self.contract_id,
+                ],
+                Some(&self.fee),
+                false,
+            )
+            .await?;
+        eprintln!(
+            "✅ Repointed {:?} to {}",
+            self.contract_name, self.contract_id
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::Cmd;
+
+    #[test]
+    fn requires_a_contract_id() {
+        let parse = |args: &[&str]| Cmd::try_parse_from([&["repoint"], args].concat());
+        assert!(parse(&["token"]).is_err());
+        let cmd = parse(&["token", "CCONTRACT"]).unwrap();
+        assert_eq!(cmd.contract_name, "token");
+        assert_eq!(cmd.contract_id, "CCONTRACT");
+    }
+}
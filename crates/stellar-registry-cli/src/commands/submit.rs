@@ -0,0 +1,131 @@
+use std::io::Read;
+
+use clap::Parser;
+
+use soroban_rpc as rpc;
+use stellar_cli::{
+    config,
+    xdr::{self, Limits, ReadXdr, ScVal, TransactionEnvelope},
+};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Signed transaction envelope as base64 XDR, as built with `--build-only`, or `-` to read it
+    /// from stdin
+    pub xdr: String,
+    #[command(flatten)]
+    pub config: config::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Rpc(#[from] rpc::Error),
+    #[error(transparent)]
+    Config(#[from] config::Error),
+    #[error(transparent)]
+    Xdr(#[from] xdr::Error),
+    #[error(transparent)]
+    SpecTools(#[from] soroban_spec_tools::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        let xdr = if self.xdr == "-" {
+            let mut xdr = String::new();
+            std::io::stdin().read_to_string(&mut xdr)?;
+            xdr
+        } else {
+            self.xdr.clone()
+        };
+        let envelope = TransactionEnvelope::from_xdr_base64(xdr.trim(), Limits::none())?;
+        let res = rpc::Client::new(&self.config.get_network()?.rpc_url)?
+            .send_transaction_polling(&envelope)
+            .await?;
+        let return_value = res.return_value()?;
+        if return_value != ScVal::Void {
+            println!("{}", soroban_spec_tools::to_json(&return_value)?);
+        }
+        eprintln!("✅ Submitted transaction");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "integration-tests")]
+    #[tokio::test]
+    async fn test_run() {
+        use super::*;
+        use std::env;
+        use stellar_scaffold_test::{AssertExt, RegistryTest};
+        let registry = RegistryTest::new().await;
+        let test_env = registry.clone().env;
+
+        env::set_var("STELLAR_RPC_URL", "http://localhost:8000/soroban/rpc");
+        env::set_var("STELLAR_ACCOUNT", "alice");
+        env::set_var(
+            "STELLAR_NETWORK_PASSPHRASE",
+            "Standalone Network ; February 2017",
+        );
+        env::set_var("STELLAR_REGISTRY_CONTRACT_ID", &registry.registry_address);
+
+        let wasm_path = test_env
+            .cwd
+            .join("target/stellar/soroban_hello_world_contract.wasm");
+        registry
+            .registry_cli("publish")
+            .arg("--wasm")
+            .arg(&wasm_path)
+            .arg("--binver")
+            .arg("0.0.1")
+            .arg("--wasm-name")
+            .arg("hello")
+            .assert()
+            .success();
+
+        // Export the deploy, which must decode and carry no signatures yet
+        let exported = registry
+            .registry_cli("deploy")
+            .arg("--contract-name")
+            .arg("hello")
+            .arg("--wasm-name")
+            .arg("hello")
+            .arg("--build-only")
+            .assert()
+            .success()
+            .stdout_as_str();
+        let TransactionEnvelope::Tx(envelope) =
+            TransactionEnvelope::from_xdr_base64(exported.trim(), Limits::none()).unwrap()
+        else {
+            panic!("expected a v1 envelope");
+        };
+        assert!(envelope.signatures.is_empty());
+
+        // Sign it as alice would elsewhere, then submit it
+        let cmd = Cmd::try_parse_from([
+            "submit",
+            "-",
+            "--source-account",
+            "alice",
+            "--config-dir",
+            test_env.cwd.to_str().unwrap(),
+        ])
+        .unwrap();
+        let signed = cmd.config.sign(envelope.tx).await.unwrap();
+        registry
+            .registry_cli("submit")
+            .arg(signed.to_xdr_base64(Limits::none()).unwrap())
+            .assert()
+            .success();
+
+        registry
+            .registry_cli("info")
+            .arg("--contract")
+            .arg("hello")
+            .assert()
+            .success();
+    }
+}
@@ -0,0 +1,43 @@
+use clap::Parser;
+
+use stellar_cli::{commands::contract::invoke, fee};
+
+use crate::{contract::NetworkContract, registries};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Name of published wasm
+    pub wasm_name: String,
+    /// Address or key name of the new author, who will be able to publish and manage maintainers
+    pub new_author: String,
+    #[command(flatten)]
+    pub config: registries::Args,
+    #[command(flatten)]
+    pub fee: fee::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        self.config
+            .invoke_registry(
+                &[
+                    "transfer_author",
+                    "--wasm_name",
+                    &self.wasm_name,
+                    "--new_author",
+                    &self.new_author,
+                ],
+                Some(&self.fee),
+                false,
+            )
+            .await?;
+        eprintln!("✅ Transferred {:?} to {}", self.wasm_name, self.new_author);
+        Ok(())
+    }
+}
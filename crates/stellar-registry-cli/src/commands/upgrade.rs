@@ -0,0 +1,160 @@
+use clap::Parser;
+
+use stellar_cli::{commands::contract::invoke, fee};
+
+use crate::{contract::NetworkContract, deprecation, registries};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Name of deployed contract to upgrade
+    #[arg(long)]
+    pub contract_name: String,
+    /// Name of published wasm to upgrade to
+    #[arg(long)]
+    pub wasm_name: String,
+    /// Version of the wasm to upgrade to, either exact or a semver requirement such as `^1.2`.
+    /// Defaults to the latest version
+    #[arg(long)]
+    pub version: Option<String>,
+    /// Name of the contract's upgrade function, defaults to `upgrade`
+    #[arg(long)]
+    pub upgrade_fn: Option<String>,
+    #[command(flatten)]
+    pub config: registries::Args,
+    #[command(flatten)]
+    pub fee: fee::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+    #[error(transparent)]
+    Deprecation(#[from] deprecation::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        deprecation::warn_if_deprecated(&self.config, &self.wasm_name).await?;
+        let mut slop = vec![
+            "upgrade_contract",
+            "--name",
+            &self.contract_name,
+            "--wasm_name",
+            &self.wasm_name,
+        ];
+        if let Some(version) = &self.version {
+            slop.extend(["--version", version]);
+        }
+        if let Some(upgrade_fn) = &self.upgrade_fn {
+            slop.extend(["--upgrade_fn", upgrade_fn]);
+        }
+        let res = self
+            .config
+            .invoke_registry(&slop, Some(&self.fee), false)
+            .await?;
+        if self.fee.build_only {
+            println!("{res}");
+            return Ok(());
+        }
+        let contract_id: String = serde_json::from_str(&res)?;
+        let info: serde_json::Value = serde_json::from_str(
+            &self
+                .config
+                .invoke_registry(
+                    &[
+                        "fetch_contract_info",
+                        "--contract_name",
+                        &self.contract_name,
+                    ],
+                    None,
+                    true,
+                )
+                .await?,
+        )?;
+        eprintln!(
+            "✅ Upgraded {:?} to {}@{}",
+            self.contract_name,
+            self.wasm_name,
+            info["version"].as_str().unwrap_or_default()
+        );
+        println!("{contract_id}");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "integration-tests")]
+    #[tokio::test]
+    async fn test_run() {
+        use std::env;
+        use stellar_scaffold_test::{find_registry_wasm, AssertExt, RegistryTest};
+        let registry = RegistryTest::new().await;
+
+        env::set_var("STELLAR_RPC_URL", "http://localhost:8000/soroban/rpc");
+        env::set_var("STELLAR_ACCOUNT", "alice");
+        env::set_var(
+            "STELLAR_NETWORK_PASSPHRASE",
+            "Standalone Network ; February 2017",
+        );
+        env::set_var("STELLAR_REGISTRY_CONTRACT_ID", &registry.registry_address);
+
+        // The registry wasm is upgradeable through its admin's `redeploy`
+        let wasm_path = find_registry_wasm().unwrap();
+        for version in ["0.0.1", "0.0.2", "0.1.0"] {
+            registry
+                .registry_cli("publish")
+                .arg("--wasm")
+                .arg(&wasm_path)
+                .arg("--binver")
+                .arg(version)
+                .arg("--wasm-name")
+                .arg("upgradeable")
+                .assert()
+                .success();
+        }
+        registry
+            .registry_cli("deploy")
+            .arg("--contract-name")
+            .arg("upgradeable")
+            .arg("--wasm-name")
+            .arg("upgradeable")
+            .arg("--version")
+            .arg("0.0.1")
+            .arg("--")
+            .arg("--admin")
+            .arg("alice")
+            .assert()
+            .success();
+
+        let stderr = registry
+            .registry_cli("upgrade")
+            .arg("--contract-name")
+            .arg("upgradeable")
+            .arg("--wasm-name")
+            .arg("upgradeable")
+            .arg("--version")
+            .arg("^0.0")
+            .arg("--upgrade-fn")
+            .arg("redeploy")
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(
+            stderr.contains("Upgraded \"upgradeable\" to upgradeable@0.0.2"),
+            "{stderr}"
+        );
+
+        let info = registry
+            .registry_cli("info")
+            .arg("--contract")
+            .arg("upgradeable")
+            .assert()
+            .success()
+            .stdout_as_str();
+        assert!(info.contains("version: 0.0.2"), "{info}");
+    }
+}
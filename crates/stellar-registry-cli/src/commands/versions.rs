@@ -0,0 +1,43 @@
+use clap::Parser;
+
+use stellar_cli::commands::contract::invoke;
+
+use crate::{contract::NetworkContract, registries};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Name of published wasm
+    pub wasm_name: String,
+    #[command(flatten)]
+    pub config: registries::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        for version in self.versions().await? {
+            println!("{version}");
+        }
+        Ok(())
+    }
+
+    /// Published versions of the wasm, oldest first
+    pub async fn versions(&self) -> Result<Vec<String>, Error> {
+        let raw = self
+            .config
+            .invoke_registry(
+                &["list_versions", "--wasm_name", &self.wasm_name],
+                None,
+                true,
+            )
+            .await?;
+        Ok(serde_json::from_str(&raw)?)
+    }
+}
@@ -0,0 +1,48 @@
+use clap::Parser;
+
+use stellar_cli::{commands::contract::invoke, fee};
+
+use crate::{contract::NetworkContract, registries};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Name of published wasm
+    pub wasm_name: String,
+    /// Version to yank
+    pub version: String,
+    /// Why the version was yanked, shown to anyone deploying it
+    #[arg(long)]
+    pub reason: String,
+    #[command(flatten)]
+    pub config: registries::Args,
+    #[command(flatten)]
+    pub fee: fee::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        self.config
+            .invoke_registry(
+                &[
+                    "yank",
+                    "--wasm_name",
+                    &self.wasm_name,
+                    "--version",
+                    &self.version,
+                    "--reason",
+                    &self.reason,
+                ],
+                Some(&self.fee),
+                false,
+            )
+            .await?;
+        eprintln!("✅ Yanked version {} of {:?}", self.version, self.wasm_name);
+        Ok(())
+    }
+}
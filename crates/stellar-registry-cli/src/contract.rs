@@ -1,12 +1,28 @@
 use sha2::{Digest, Sha256};
 
+use crate::registries::{self, Registries};
 use soroban_rpc as rpc;
 use stellar_cli::{
-    commands::{contract::invoke, NetworkRunnable},
+    assembled::simulate_and_assemble_transaction,
+    commands::{contract::invoke, txn_result::TxnResult, NetworkRunnable},
     config::{self, network::Network, UnresolvedContract},
     xdr::{self, WriteXdr},
 };
 
+/// Errors of the registry contract that the CLI handles, numbered as in the contract
+#[derive(Clone, Copy, Debug)]
+pub enum RegistryError {
+    NoSuchMetadata = 21,
+    NoSuchContractInfo = 22,
+}
+
+/// Whether invoking the registry failed because the contract returned `expected`
+pub fn is_registry_error(error: &invoke::Error, expected: RegistryError) -> bool {
+    let message = error.to_string();
+    message.contains(&format!("Error(Contract, #{})", expected as u32))
+        || message.contains(&format!("{expected:?}"))
+}
+
 pub trait NetworkContract {
     fn contract_id(&self) -> Result<stellar_strkey::Contract, config::Error>;
 
@@ -26,19 +42,23 @@ pub trait NetworkContract {
     fn rpc_client(&self) -> Result<rpc::Client, config::Error>;
 }
 
-impl NetworkContract for config::Args {
+impl NetworkContract for registries::Args {
     fn contract_id(&self) -> Result<stellar_strkey::Contract, config::Error> {
         let Network {
             network_passphrase, ..
         } = &self.get_network()?;
-        let contract: UnresolvedContract = unsafe {
-            if let Ok(contract_id) = std::env::var("STELLAR_REGISTRY_CONTRACT_ID") {
-                contract_id.parse()
-            } else {
-                contract_id(network_passphrase).to_string().parse()
-            }
-            .unwrap_unchecked()
+        let registries = Registries::load(&self.locator)?;
+        // `--registry` first, then the environment, then the network's default registry
+        let registry_id = if let Some(registry) = &self.registry {
+            registries.resolve(registry)
+        } else if let Ok(contract_id) = std::env::var("STELLAR_REGISTRY_CONTRACT_ID") {
+            contract_id
+        } else {
+            registries
+                .default_for(network_passphrase)
+                .unwrap_or_else(|| contract_id(network_passphrase).to_string())
         };
+        let contract: UnresolvedContract = registry_id.parse().map_err(registries::invalid_data)?;
         Ok(contract.resolve_contract_id(&self.locator, network_passphrase)?)
     }
 
@@ -58,31 +78,47 @@ impl NetworkContract for config::Args {
 
 pub fn build_invoke_cmd(
     slop: &[&str],
-    config: &stellar_cli::config::Args,
+    config: &registries::Args,
     fee: Option<&stellar_cli::fee::Args>,
     view_only: bool,
 ) -> Result<invoke::Cmd, config::Error> {
     Ok(invoke::Cmd {
         contract_id: UnresolvedContract::Resolved(config.contract_id()?),
         slop: slop.iter().map(Into::into).collect(),
-        config: config.clone(),
+        config: config.config.clone(),
         fee: fee.cloned().unwrap_or_default(),
         send: view_only.then_some(invoke::Send::No).unwrap_or_default(),
         ..Default::default()
     })
 }
 
+/// Invoke the registry and return the JSON result. With `--build-only` the transaction is
+/// simulated and returned as an unsigned base64 transaction envelope instead of being sent.
 pub async fn invoke_registry(
     slop: &[&str],
-    config: &stellar_cli::config::Args,
+    config: &registries::Args,
     fee: Option<&stellar_cli::fee::Args>,
     view_only: bool,
 ) -> Result<String, invoke::Error> {
-    Ok(build_invoke_cmd(slop, config, fee, view_only)?
+    match build_invoke_cmd(slop, config, fee, view_only)?
         .run_against_rpc_server(Some(&stellar_cli::commands::global::Args::default()), None)
         .await?
-        .into_result()
-        .expect("Failed to parse JSON"))
+    {
+        TxnResult::Res(res) => Ok(res),
+        TxnResult::Txn(tx) => {
+            let assembled = simulate_and_assemble_transaction(&config.rpc_client()?, &tx).await?;
+            Ok(unsigned_envelope(assembled.transaction().clone())?)
+        }
+    }
+}
+
+/// Base64 XDR of `tx` wrapped in an envelope without signatures, to be signed elsewhere
+pub fn unsigned_envelope(tx: xdr::Transaction) -> Result<String, xdr::Error> {
+    xdr::TransactionEnvelope::Tx(xdr::TransactionV1Envelope {
+        tx,
+        signatures: xdr::VecM::default(),
+    })
+    .to_xdr_base64(xdr::Limits::none())
 }
 
 pub fn stellar_address() -> stellar_strkey::ed25519::PublicKey {
@@ -153,3 +189,29 @@ mod generate_id {
         ));
     }
 }
+
+#[cfg(test)]
+mod envelope {
+    use stellar_cli::xdr::{self, ReadXdr};
+
+    #[test]
+    fn unsigned_envelope_decodes_without_signatures() {
+        let tx = xdr::Transaction {
+            source_account: xdr::MuxedAccount::Ed25519(xdr::Uint256(super::stellar_address().0)),
+            fee: 100,
+            seq_num: xdr::SequenceNumber(1),
+            cond: xdr::Preconditions::None,
+            memo: xdr::Memo::None,
+            operations: xdr::VecM::default(),
+            ext: xdr::TransactionExt::V0,
+        };
+        let exported = super::unsigned_envelope(tx.clone()).unwrap();
+        match xdr::TransactionEnvelope::from_xdr_base64(&exported, xdr::Limits::none()).unwrap() {
+            xdr::TransactionEnvelope::Tx(envelope) => {
+                assert_eq!(envelope.tx, tx);
+                assert!(envelope.signatures.is_empty());
+            }
+            envelope => panic!("expected a v1 envelope, got {envelope:?}"),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Warnings shown before deploying or installing a deprecated wasm
+use stellar_cli::commands::contract::invoke;
+
+use crate::{
+    contract::{is_registry_error, NetworkContract, RegistryError},
+    registries,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Invoke(#[from] invoke::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// Warn if the published wasm `wasm_name` is deprecated
+pub async fn warn_if_deprecated(config: &registries::Args, wasm_name: &str) -> Result<(), Error> {
+    let raw = config
+        .invoke_registry(&["fetch_deprecation", "--wasm_name", wasm_name], None, true)
+        .await?;
+    if let Some(message) = serde_json::from_str::<Option<String>>(&raw)? {
+        eprintln!("⚠️ {wasm_name:?} is deprecated: {message}");
+    }
+    Ok(())
+}
+
+/// Warn if the deployed contract `contract_name` runs a deprecated wasm
+pub async fn warn_if_contract_deprecated(
+    config: &registries::Args,
+    contract_name: &str,
+) -> Result<(), Error> {
+    let raw = match config
+        .invoke_registry(
+            &["fetch_contract_info", "--contract_name", contract_name],
+            None,
+            true,
+        )
+        .await
+    {
+        Ok(raw) => raw,
+        // Contracts deployed before the registry recorded deployments have no info
+        Err(e) if is_registry_error(&e, RegistryError::NoSuchContractInfo) => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    let info: serde_json::Value = serde_json::from_str(&raw)?;
+    match info["wasm_name"].as_str() {
+        Some(wasm_name) => warn_if_deprecated(config, wasm_name).await,
+        None => Ok(()),
+    }
+}
@@ -5,6 +5,8 @@
 )]
 pub mod commands;
 pub mod contract;
+pub mod deprecation;
+pub mod registries;
 
 pub use commands::Root;
 use std::path::Path;
@@ -0,0 +1,220 @@
+//! Named registries, configured in `registries.toml` in the `stellar-cli` config directory:
+//!
+//! ```toml
+//! [registries]
+//! public = "CBCOGWBDGBFWR5LQFKRQUPFIG6OLOON35PBKUPB6C542DFZI3OMBOGHX"
+//! org = "org-registry"
+//!
+//! [default]
+//! "Test SDF Network ; September 2015" = "org"
+//! ```
+//!
+//! Each registry is a contract ID or a `stellar contract alias`, and `[default]` picks the
+//! registry used on each network, by network passphrase, when `--registry` isn't passed.
+use std::{collections::BTreeMap, fs, io, ops::Deref, path::PathBuf};
+
+use serde_derive::{Deserialize, Serialize};
+use stellar_cli::config::{self, locator};
+
+const FILE_NAME: &str = "registries.toml";
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Registry {reference:?} conflicts with --registry {selected}")]
+    ConflictingRegistry { reference: String, selected: String },
+}
+
+/// `stellar-cli` config along with the registry to use
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct Args {
+    /// Named registry from `registries.toml`, or a registry contract ID or alias, to use instead
+    /// of the network's default registry
+    #[arg(long, env = "STELLAR_REGISTRY")]
+    pub registry: Option<String>,
+    #[command(flatten)]
+    pub config: config::Args,
+}
+
+impl Deref for Args {
+    type Target = config::Args;
+
+    fn deref(&self) -> &Self::Target {
+        &self.config
+    }
+}
+
+impl Args {
+    /// These args with the registry named in an `alias:name` reference selected
+    pub fn for_ref(&self, reference: &str) -> Result<Self, Error> {
+        let Some(registry) = split_ref(reference).0 else {
+            return Ok(self.clone());
+        };
+        match &self.registry {
+            Some(selected) if selected != registry => Err(Error::ConflictingRegistry {
+                reference: reference.to_string(),
+                selected: selected.clone(),
+            }),
+            _ => Ok(Self {
+                registry: Some(registry.to_string()),
+                config: self.config.clone(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Registries {
+    /// Contract ID or contract alias of each named registry
+    #[serde(default)]
+    pub registries: BTreeMap<String, String>,
+    /// Named registry to use on each network, by network passphrase
+    #[serde(default)]
+    pub default: BTreeMap<String, String>,
+}
+
+impl Registries {
+    fn path(locator: &locator::Args) -> Result<PathBuf, locator::Error> {
+        Ok(locator.config_dir()?.join(FILE_NAME))
+    }
+
+    pub fn load(locator: &locator::Args) -> Result<Self, locator::Error> {
+        match fs::read_to_string(Self::path(locator)?) {
+            Ok(contents) => toml::from_str(&contents).map_err(invalid_data),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(locator::Error::Io(e)),
+        }
+    }
+
+    pub fn save(&self, locator: &locator::Args) -> Result<(), locator::Error> {
+        let path = Self::path(locator)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(locator::Error::Io)?;
+        }
+        let contents = toml::to_string(self).map_err(invalid_data)?;
+        fs::write(path, contents).map_err(locator::Error::Io)
+    }
+
+    /// Contract ID or contract alias of the registry named `registry`. Names that aren't
+    /// configured are returned as is, so a contract ID or alias can be passed directly
+    pub fn resolve(&self, registry: &str) -> String {
+        self.registries
+            .get(registry)
+            .cloned()
+            .unwrap_or_else(|| registry.to_string())
+    }
+
+    /// Contract ID or contract alias of the default registry for the network with
+    /// `network_passphrase`, if one is configured
+    pub fn default_for(&self, network_passphrase: &str) -> Option<String> {
+        self.default
+            .get(network_passphrase)
+            .map(|registry| self.resolve(registry))
+    }
+}
+
+pub fn invalid_data(e: impl std::error::Error + Send + Sync + 'static) -> locator::Error {
+    locator::Error::Io(io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Split an `alias:name` reference into the registry alias, if any, and the name
+pub fn split_ref(reference: &str) -> (Option<&str>, &str) {
+    match reference.split_once(':') {
+        Some((registry, name)) => (Some(registry), name),
+        None => (None, reference),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_cli::config::locator;
+
+    use super::{split_ref, Args, Error, Registries};
+
+    #[test]
+    fn resolves_named_registries() {
+        let registries: Registries = toml::from_str(
+            r#"
+            [registries]
+            org = "CBCOGWBDGBFWR5LQFKRQUPFIG6OLOON35PBKUPB6C542DFZI3OMBOGHX"
+
+            [default]
+            "Test SDF Network ; September 2015" = "org"
+            "#,
+        )
+        .unwrap();
+        let org = "CBCOGWBDGBFWR5LQFKRQUPFIG6OLOON35PBKUPB6C542DFZI3OMBOGHX";
+        assert_eq!(registries.resolve("org"), org);
+        assert_eq!(registries.resolve("other"), "other");
+        assert_eq!(
+            registries
+                .default_for("Test SDF Network ; September 2015")
+                .as_deref(),
+            Some(org)
+        );
+        assert_eq!(
+            registries.default_for("Public Global Stellar Network ; September 2015"),
+            None
+        );
+    }
+
+    #[test]
+    fn saves_to_config_dir() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let locator = locator::Args {
+            global: false,
+            config_dir: Some(dir.path().to_path_buf()),
+        };
+        assert!(Registries::load(&locator).unwrap().registries.is_empty());
+
+        let mut registries = Registries::default();
+        registries
+            .registries
+            .insert("org".to_string(), "org-registry".to_string());
+        registries.default.insert(
+            "Standalone Network ; February 2017".to_string(),
+            "org".to_string(),
+        );
+        registries.save(&locator).unwrap();
+
+        let loaded = Registries::load(&locator).unwrap();
+        assert_eq!(loaded.registries, registries.registries);
+        assert_eq!(loaded.default, registries.default);
+    }
+
+    #[test]
+    fn splits_registry_refs() {
+        assert_eq!(split_ref("hello"), (None, "hello"));
+        assert_eq!(split_ref("org:hello"), (Some("org"), "hello"));
+        assert_eq!(
+            split_ref("org:@ahalabs/token"),
+            (Some("org"), "@ahalabs/token")
+        );
+    }
+
+    #[test]
+    fn selects_registry_from_refs() {
+        let args = Args::default();
+        assert_eq!(args.for_ref("hello").unwrap().registry, None);
+        assert_eq!(
+            args.for_ref("org:hello").unwrap().registry.as_deref(),
+            Some("org")
+        );
+
+        let args = Args {
+            registry: Some("org".to_string()),
+            ..Args::default()
+        };
+        assert_eq!(
+            args.for_ref("hello").unwrap().registry.as_deref(),
+            Some("org")
+        );
+        assert_eq!(
+            args.for_ref("org:hello").unwrap().registry.as_deref(),
+            Some("org")
+        );
+        assert!(matches!(
+            args.for_ref("public:hello"),
+            Err(Error::ConflictingRegistry { .. })
+        ));
+    }
+}
@@ -1,17 +1,23 @@
 #![allow(clippy::struct_excessive_bools)]
-use super::env_toml::Network;
+use super::deployments::{self, Deployment, Lockfile, LOCK_FILE};
+use super::env_toml::{AfterDeploy, AfterDeployStep, Network, SendMode};
+use super::interpolate::{self, Reference};
 use crate::arg_parsing;
 use crate::arg_parsing::ArgParser;
 use crate::commands::build::clients::Error::UpgradeArgsError;
-use crate::commands::build::env_toml;
+use crate::commands::build::env_toml::{self, Mode};
+use crate::commands::plan::{Action, ContractPlan, Plan};
 use indexmap::IndexMap;
+use itertools::Either;
 use regex::Regex;
 use serde_json;
 use shlex::split;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::path::Path;
 use std::process::Command;
+use std::sync::Arc;
 use stellar_cli::{
     commands as cli,
     commands::contract::info::shared::{
@@ -25,26 +31,63 @@ use stellar_cli::{
 };
 use stellar_strkey::{self, Contract};
 use stellar_xdr::curr::ScSpecEntry::FunctionV0;
-use stellar_xdr::curr::{Error as xdrError, ScSpecEntry, ScSpecTypeBytesN, ScSpecTypeDef};
-
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, clap::ValueEnum)]
-pub enum ScaffoldEnv {
-    Development,
-    Testing,
-    Staging,
-    Production,
+use stellar_xdr::curr::{
+    ContractDataDurability, ContractId, Error as xdrError, LedgerKey, LedgerKeyContractCode,
+    LedgerKeyContractData, ScAddress, ScSpecEntry, ScSpecTypeBytesN, ScSpecTypeDef, ScVal,
+};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use tokio::task::JoinSet;
+
+/// Name of an environment in environments.toml
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ScaffoldEnv(String);
+
+impl ScaffoldEnv {
+    pub fn development() -> Self {
+        Self("development".to_string())
+    }
+
+    pub fn production() -> Self {
+        Self("production".to_string())
+    }
+}
+
+impl std::str::FromStr for ScaffoldEnv {
+    type Err = env_toml::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || s == env_toml::DEFAULT_ENV {
+            return Err(env_toml::Error::InvalidEnvName(s.to_string()));
+        }
+        Ok(Self(s.to_string()))
+    }
 }
 
 impl std::fmt::Display for ScaffoldEnv {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", format!("{self:?}").to_lowercase())
+        write!(f, "{}", self.0)
     }
 }
 
 #[derive(clap::Args, Clone, Debug)]
 pub struct Args {
-    #[arg(env = "STELLAR_SCAFFOLD_ENV", value_enum)]
+    /// Environment from environments.toml to build for [default: production]
+    #[arg(env = "STELLAR_SCAFFOLD_ENV")]
     pub env: Option<ScaffoldEnv>,
+    /// Check every contract against the network instead of trusting `.stellar/deployments.lock`
+    #[arg(long)]
+    pub refresh_deployments: bool,
+    /// Number of contracts to upload, deploy and generate clients for at once. A contract is only
+    /// started once the contracts it depends on are done
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: u16,
+    /// Run the `after_deploy` steps of this contract again, including those that already
+    /// completed. Can be given multiple times.
+    #[arg(long, value_name = "CONTRACT")]
+    pub rerun_after_deploy: Vec<String>,
+    /// Contract names mapped to the names of the contracts they depend on
+    #[arg(skip)]
+    pub contract_dependencies: HashMap<String, Vec<String>>,
     #[arg(skip)]
     pub workspace_root: Option<std::path::PathBuf>,
     /// Directory where wasm files are located
@@ -78,7 +121,7 @@ pub enum Error {
     BadContractName(String),
     #[error("⛔ ️Invalid contract ID: {0:?}")]
     InvalidContractID(String),
-    #[error("⛔ ️An ID must be set for a contract in production or staging. E.g. <name>.id = C...")]
+    #[error("⛔ ️An ID must be set for a contract in an environment with mode = \"pinned-ids\". E.g. <name>.id = C...")]
     MissingContractID(String),
     #[error("⛔ ️Unable to parse script: {0:?}")]
     ScriptParseFailure(String),
@@ -120,6 +163,24 @@ pub enum Error {
     FetchError(#[from] FetchError),
     #[error(transparent)]
     SpecError(#[from] stellar_cli::get_spec::contract_spec::Error),
+    #[error(transparent)]
+    Deployments(#[from] deployments::Error),
+    #[error(transparent)]
+    Interpolation(#[from] interpolate::Error),
+    #[error("⛔ ️{0:?} references contract {1:?} with ${{contract:{1}}}, but {1:?} is not deployed in this environment")]
+    UnknownContractReference(String, String),
+    #[error("⛔ ️contracts reference each other in a cycle: {0}")]
+    DeployCycle(String),
+    #[error("⛔ ️invalid after_deploy step for {0:?}: {1}")]
+    InvalidAfterDeployStep(String, String),
+    #[error("⛔ ️contract task failed: {0}")]
+    ContractTask(#[from] tokio::task::JoinError),
+    #[error("⛔ ️{0:?} was never processed")]
+    ContractNotProcessed(String),
+    #[error("⛔ ️--rerun-after-deploy {0:?} is not a contract in this workspace")]
+    UnknownRerunContract(String),
+    #[error("⛔ ️STELLAR_NETWORK_PASSPHRASE is not set; set the environment's network in environments.toml")]
+    MissingNetworkPassphrase,
 }
 
 impl Args {
@@ -133,13 +194,18 @@ impl Args {
             .as_ref()
             .expect("workspace_root must be set before running");
 
-        let Some(current_env) = env_toml::Environment::get(
-            workspace_root,
-            &self.stellar_scaffold_env(ScaffoldEnv::Production),
-        )?
+        let Some(current_env) =
+            env_toml::Environment::get(workspace_root, &self.stellar_scaffold_env())?
         else {
             return Ok(());
         };
+        if let Some(unknown) = self
+            .rerun_after_deploy
+            .iter()
+            .find(|name| !package_names.contains(name))
+        {
+            return Err(Error::UnknownRerunContract(unknown.clone()));
+        }
 
         self.add_network_to_env(&current_env.network)?;
         // Create the '.stellar' directory if it doesn't exist
@@ -148,19 +214,218 @@ impl Args {
         self.clone()
             .handle_accounts(current_env.accounts.as_deref(), &current_env.network)
             .await?;
+        self.handle_network_overrides(
+            current_env.accounts.as_deref(),
+            current_env.contracts.as_ref(),
+        )
+        .await?;
         self.clone()
-            .handle_contracts(
-                current_env.contracts.as_ref(),
-                package_names,
-                &current_env.network,
-            )
+            .handle_contracts(&current_env, package_names)
             .await?;
 
         Ok(())
     }
 
-    fn stellar_scaffold_env(&self, default: ScaffoldEnv) -> String {
-        self.env.unwrap_or(default).to_string().to_lowercase()
+    /// Work out what `run` would do with each contract without sending any transactions.
+    /// Returns `None` if the workspace has no environments.toml.
+    pub async fn plan(&self, package_names: Vec<String>) -> Result<Option<Plan>, Error> {
+        let workspace_root = self
+            .workspace_root
+            .as_ref()
+            .expect("workspace_root must be set before running");
+        let env = self.stellar_scaffold_env();
+
+        let Some(current_env) = env_toml::Environment::get(workspace_root, &env)? else {
+            return Ok(None);
+        };
+
+        self.add_network_to_env(&current_env.network)?;
+        let network_passphrase = Self::network_passphrase()?;
+        let contracts = current_env.contracts.as_ref();
+        let lockfile = Lockfile::load(workspace_root)?;
+
+        let names = if current_env.mode == Mode::PinnedIds {
+            contracts
+                .map(|contracts| contracts.keys().map(ToString::to_string).collect())
+                .unwrap_or_default()
+        } else {
+            self.validate_contract_names(contracts)?;
+            let names = Self::maintain_user_ordering(&package_names, contracts);
+            let deployed = names
+                .iter()
+                .filter(|name| Self::contract_settings(contracts, name).client)
+                .cloned()
+                .collect::<Vec<_>>();
+            self.deploy_dependencies(&deployed, contracts)?;
+            names
+        };
+
+        let mut planned = Vec::with_capacity(names.len());
+        let mut contract_ids = HashMap::new();
+        for name in names {
+            let settings = Self::contract_settings(contracts, &name);
+            let contract_passphrase = self.contract_network_passphrase(&settings)?;
+            let locked = lockfile.get(&env, &contract_passphrase, &name);
+            let plan = self
+                .plan_single_contract(&name, &settings, &current_env, locked, &contract_ids)
+                .await?;
+            if let Some(contract_id) = &plan.contract_id {
+                contract_ids.insert(name.clone(), contract_id.clone());
+            }
+            planned.push(plan);
+        }
+
+        Ok(Some(Plan {
+            env,
+            network_passphrase,
+            contracts: planned,
+        }))
+    }
+
+    async fn plan_single_contract(
+        &self,
+        name: &str,
+        settings: &env_toml::Contract,
+        current_env: &env_toml::Environment,
+        locked: Option<&Deployment>,
+        contract_ids: &HashMap<String, String>,
+    ) -> Result<ContractPlan, Error> {
+        let mut plan = ContractPlan {
+            name: name.to_string(),
+            action: Action::Skip,
+            upload: false,
+            wasm_hash: None,
+            contract_id: None,
+            network_passphrase: None,
+            after_deploy: Vec::new(),
+        };
+        if !settings.client {
+            return Ok(plan);
+        }
+        if let Some(network) = &settings.network {
+            plan.network_passphrase = Some(self.resolve_network(network)?.network_passphrase);
+        }
+        let network = settings.network.as_ref().unwrap_or(&current_env.network);
+        if let Some(id) = &settings.id {
+            Contract::from_string(id).map_err(|_| Error::InvalidContractID(id.clone()))?;
+            plan.action = Action::Pinned;
+            plan.contract_id = Some(id.clone());
+            return Ok(plan);
+        }
+        if current_env.mode == Mode::PinnedIds {
+            return Err(Error::MissingContractID(name.to_string()));
+        }
+
+        let wasm_path = self.get_wasm_path(name);
+        if !wasm_path.exists() {
+            return Err(Error::BadContractName(name.to_string()));
+        }
+        if current_env.run_after_deploy {
+            Self::validate_after_deploy(name, settings.after_deploy.as_ref(), &wasm_path)?;
+        }
+        let wasm_hash = deployments::wasm_hash(&wasm_path)?;
+        plan.upload = !self.wasm_is_uploaded(&wasm_hash, network).await?;
+        plan.action = Action::Deploy;
+
+        let mut context = None;
+        if let Some(existing_contract_id) = self.get_contract_alias(name, network)? {
+            if let Some(current_hash) = self
+                .get_contract_hash(&existing_contract_id, network)
+                .await?
+            {
+                plan.contract_id = Some(existing_contract_id.to_string());
+                let settings_context = context.insert(
+                    self.settings_context(name, &existing_contract_id, settings, contract_ids)
+                        .await?,
+                );
+                let constructor_args_changed = match locked {
+                    Some(locked) => !locked.same_constructor_args(settings, settings_context)?,
+                    None => false,
+                };
+                if constructor_args_changed {
+                    // Redeployed so the constructor runs with the new args
+                } else if current_hash == wasm_hash {
+                    plan.action = Action::UpToDate;
+                } else if Self::is_legacy_upgradeable(
+                    fetch_contract_spec(&current_hash, network).await?,
+                )
+                .is_some()
+                    && Self::is_legacy_upgradeable(Spec::new(&std::fs::read(&wasm_path)?)?.spec)
+                        .is_some()
+                {
+                    plan.action = Action::Upgrade;
+                }
+            }
+        }
+
+        if let (Some(after_deploy), true) = (&settings.after_deploy, current_env.run_after_deploy) {
+            // Deploys, upgrades and reruns run every step; otherwise only steps that are recorded
+            // as not completed yet run
+            let rerun = plan.action != Action::UpToDate
+                || self.rerun_after_deploy.iter().any(|rerun| rerun == name);
+            let locked =
+                locked.filter(|locked| Some(&locked.contract_id) == plan.contract_id.as_ref());
+            let steps: Vec<String> = match after_deploy {
+                AfterDeploy::Script(script) => script
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(ToString::to_string)
+                    .collect(),
+                AfterDeploy::Steps(steps) => steps.iter().map(ToString::to_string).collect(),
+            };
+            plan.after_deploy = match (rerun, locked, &context) {
+                (true, _, _) => steps,
+                // Only contracts that are up to date skip steps, and those have a context
+                (false, Some(locked), Some(context)) => steps
+                    .into_iter()
+                    .zip(deployments::after_deploy_step_keys(after_deploy, context)?)
+                    .filter(|(_, key)| !locked.after_deploy_step_done(key))
+                    .map(|(step, _)| step)
+                    .collect(),
+                _ => Vec::new(),
+            };
+        }
+        plan.wasm_hash = Some(wasm_hash);
+        Ok(plan)
+    }
+
+    /// Whether wasm with the given hash has already been uploaded, using a read-only ledger lookup
+    async fn wasm_is_uploaded(&self, wasm_hash: &str, network: &Network) -> Result<bool, Error> {
+        let rpc_client = soroban_rpc::Client::new(&self.resolve_network(network)?.rpc_url)?;
+        let key = LedgerKey::ContractCode(LedgerKeyContractCode {
+            hash: wasm_hash.parse::<stellar_xdr::curr::Hash>()?,
+        });
+        let response = rpc_client.get_ledger_entries(&[key]).await?;
+        Ok(response.entries.is_some_and(|entries| !entries.is_empty()))
+    }
+
+    /// Whether the contract instance still exists, using a read-only ledger lookup
+    async fn contract_instance_exists(
+        &self,
+        contract_id: &Contract,
+        network: &Network,
+    ) -> Result<bool, Error> {
+        let rpc_client = soroban_rpc::Client::new(&self.resolve_network(network)?.rpc_url)?;
+        let key = LedgerKey::ContractData(LedgerKeyContractData {
+            contract: ScAddress::Contract(ContractId(stellar_xdr::curr::Hash(contract_id.0))),
+            key: ScVal::LedgerKeyContractInstance,
+            durability: ContractDataDurability::Persistent,
+        });
+        let response = rpc_client.get_ledger_entries(&[key]).await?;
+        Ok(response.entries.is_some_and(|entries| !entries.is_empty()))
+    }
+
+    /// The network passphrase that `add_network_to_env` set for the current environment
+    fn network_passphrase() -> Result<String, Error> {
+        std::env::var("STELLAR_NETWORK_PASSPHRASE").map_err(|_| Error::MissingNetworkPassphrase)
+    }
+
+    fn stellar_scaffold_env(&self) -> String {
+        self.env
+            .clone()
+            .unwrap_or_else(ScaffoldEnv::production)
+            .to_string()
     }
 
     /// Parse the network settings from the environments.toml file and set `STELLAR_RPC_URL` and
@@ -217,6 +482,45 @@ impl Args {
         }
     }
 
+    /// Look up the RPC url and passphrase of a network from environments.toml, resolving named
+    /// networks from the stellar config
+    fn resolve_network(
+        &self,
+        network: &Network,
+    ) -> Result<stellar_cli::config::network::Network, Error> {
+        match network {
+            Network { name: Some(_), .. }
+            | Network {
+                rpc_url: Some(_),
+                network_passphrase: Some(_),
+                ..
+            } => Ok(Self::get_network_args(network).get(&self.get_config_locator())?),
+            _ => Err(Error::MalformedNetwork),
+        }
+    }
+
+    /// `--rpc-url` and `--network-passphrase` for stellar CLI commands, so that contracts with a
+    /// network override do not use the environment's network from `STELLAR_RPC_URL` and
+    /// `STELLAR_NETWORK_PASSPHRASE`
+    fn network_args(&self, network: &Network) -> Result<Vec<String>, Error> {
+        let network = self.resolve_network(network)?;
+        Ok(vec![
+            "--rpc-url".to_string(),
+            network.rpc_url,
+            "--network-passphrase".to_string(),
+            network.network_passphrase,
+        ])
+    }
+
+    /// Passphrase of the network a contract is deployed to: its own `network` if set, otherwise
+    /// the environment's
+    fn contract_network_passphrase(&self, settings: &env_toml::Contract) -> Result<String, Error> {
+        if let Some(network) = &settings.network {
+            return Ok(self.resolve_network(network)?.network_passphrase);
+        }
+        Self::network_passphrase()
+    }
+
     fn get_config_locator(&self) -> stellar_cli::config::locator::Args {
         let workspace_root = self
             .workspace_root
@@ -228,14 +532,10 @@ impl Args {
         }
     }
 
-    fn get_contract_alias(
-        &self,
-        name: &str,
-    ) -> Result<Option<Contract>, stellar_cli::config::locator::Error> {
+    fn get_contract_alias(&self, name: &str, network: &Network) -> Result<Option<Contract>, Error> {
         let config_dir = self.get_config_locator();
-        let network_passphrase = std::env::var("STELLAR_NETWORK_PASSPHRASE")
-            .expect("No STELLAR_NETWORK_PASSPHRASE environment variable set");
-        config_dir.get_contract_id(name, &network_passphrase)
+        let network_passphrase = self.resolve_network(network)?.network_passphrase;
+        Ok(config_dir.get_contract_id(name, &network_passphrase)?)
     }
 
     async fn get_contract_hash(
@@ -272,33 +572,47 @@ impl Args {
         name: &str,
         contract_id: &Contract,
         network: &Network,
-    ) -> Result<(), stellar_cli::config::locator::Error> {
+    ) -> Result<(), Error> {
         let config_dir = self.get_config_locator();
-        let passphrase = network
-            .network_passphrase
-            .clone()
-            .expect("You must set a network passphrase.");
-        config_dir.save_contract_id(&passphrase, contract_id, name)
+        let passphrase = self.resolve_network(network)?.network_passphrase;
+        Ok(config_dir.save_contract_id(&passphrase, contract_id, name)?)
     }
 
-    fn create_contract_template(&self, name: &str, contract_id: &str) -> Result<(), Error> {
-        let allow_http = if ["development", "test"]
-            .contains(&self.stellar_scaffold_env(ScaffoldEnv::Production).as_str())
-        {
+    /// Write the client module for a contract. Contracts with their own `network` are wired to
+    /// that network's RPC url instead of the app-wide one from `./util`.
+    fn create_contract_template(
+        &self,
+        name: &str,
+        contract_id: &str,
+        network_override: Option<&Network>,
+        mut allow_http: bool,
+    ) -> Result<(), Error> {
+        let (network, rpc_url_import, rpc_url) = if let Some(network) = network_override {
+            let network = self.resolve_network(network)?;
+            allow_http |= network.rpc_url.starts_with("http://");
+            (
+                network.network_passphrase,
+                String::new(),
+                format!("\n  rpcUrl: '{}',", network.rpc_url),
+            )
+        } else {
+            (
+                Self::network_passphrase()?,
+                "\nimport { rpcUrl } from './util';".to_string(),
+                "\n  rpcUrl,".to_string(),
+            )
+        };
+        let allow_http = if allow_http {
             "\n  allowHttp: true,"
         } else {
             ""
         };
-        let network = std::env::var("STELLAR_NETWORK_PASSPHRASE")
-            .expect("No STELLAR_NETWORK_PASSPHRASE environment variable set");
         let template = format!(
-            r"import * as Client from '{name}';
-import {{ rpcUrl }} from './util';
+            r"import * as Client from '{name}';{rpc_url_import}
 
 export default new Client.Client({{
   networkPassphrase: '{network}',
-  contractId: '{contract_id}',
-  rpcUrl,{allow_http}
+  contractId: '{contract_id}',{rpc_url}{allow_http}
   publicKey: undefined,
 }});
 "
@@ -312,7 +626,51 @@ export default new Client.Client({{
         Ok(())
     }
 
-    async fn generate_contract_bindings(&self, name: &str, contract_id: &str) -> Result<(), Error> {
+    async fn generate_typescript_bindings(
+        &self,
+        contract_id: &str,
+        output_dir: &Path,
+        network_override: Option<&Network>,
+    ) -> Result<(), Error> {
+        let workspace_root = self
+            .workspace_root
+            .as_ref()
+            .expect("workspace_root not set");
+        let mut bindings_args = vec![
+            "--contract-id".to_string(),
+            contract_id.to_string(),
+            "--output-dir".to_string(),
+            output_dir
+                .to_str()
+                .expect("we do not support non-utf8 paths")
+                .to_string(),
+            "--config-dir".to_string(),
+            workspace_root
+                .to_str()
+                .expect("we do not support non-utf8 paths")
+                .to_string(),
+            "--overwrite".to_string(),
+        ];
+        if let Some(network) = network_override {
+            bindings_args.extend(self.network_args(network)?);
+        }
+        let bindings_arg_refs: Vec<&str> = bindings_args
+            .iter()
+            .map(std::string::String::as_str)
+            .collect();
+        cli::contract::bindings::typescript::Cmd::parse_arg_vec(&bindings_arg_refs)?
+            .run_against_rpc_server(self.global_args.as_ref(), None)
+            .await?;
+        Ok(())
+    }
+
+    async fn generate_contract_bindings(
+        &self,
+        name: &str,
+        contract_id: &str,
+        network_override: Option<&Network>,
+        allow_http: bool,
+    ) -> Result<(), Error> {
         let printer = self.printer();
         printer.infoln(format!("Binding {name:?} contract"));
         let workspace_root = self
@@ -325,28 +683,18 @@ export default new Client.Client({{
         let temp_dir = workspace_root.join(format!("target/packages/{name}"));
         let temp_dir_display = temp_dir.display();
 
-        cli::contract::bindings::typescript::Cmd::parse_arg_vec(&[
-            "--contract-id",
-            contract_id,
-            "--output-dir",
-            temp_dir.to_str().expect("we do not support non-utf8 paths"),
-            "--config-dir",
-            workspace_root
-                .to_str()
-                .expect("we do not support non-utf8 paths"),
-            "--overwrite",
-        ])?
-        .run_against_rpc_server(self.global_args.as_ref(), None)
-        .await?;
+        self.generate_typescript_bindings(contract_id, &temp_dir, network_override)
+            .await?;
 
         // Run `npm i` in the temp directory
         printer.infoln(format!("Running 'npm install' in {temp_dir_display:?}"));
-        let output = std::process::Command::new("npm")
+        let output = tokio::process::Command::new("npm")
             .current_dir(&temp_dir)
             .arg("install")
             .arg("--loglevel=error") // Reduce noise from warnings
             .arg("--no-workspaces") // fix issue where stellar sometimes isnt installed locally causing tsc to fail
-            .output()?;
+            .output()
+            .await?;
 
         if !output.status.success() {
             // Clean up temp directory on failure
@@ -363,12 +711,13 @@ export default new Client.Client({{
         printer.checkln(format!("'npm install' succeeded in {temp_dir_display}"));
 
         printer.infoln(format!("Running 'npm run build' in {temp_dir_display}"));
-        let output = std::process::Command::new("npm")
+        let output = tokio::process::Command::new("npm")
             .current_dir(&temp_dir)
             .arg("run")
             .arg("build")
             .arg("--loglevel=error") // Reduce noise from warnings
-            .output()?;
+            .output()
+            .await?;
 
         if !output.status.success() {
             // Clean up temp directory on failure
@@ -399,11 +748,12 @@ export default new Client.Client({{
             std::fs::rename(&temp_dir, &final_output_dir)?;
             printer.checkln(format!("Client {name:?} created successfully"));
             // Run npm install in the final output directory to ensure proper linking
-            let output = std::process::Command::new("npm")
+            let output = tokio::process::Command::new("npm")
                 .current_dir(&final_output_dir)
                 .arg("install")
                 .arg("--loglevel=error")
-                .output()?;
+                .output()
+                .await?;
 
             if !output.status.success() {
                 return Err(Error::NpmCommandFailure(
@@ -417,7 +767,7 @@ export default new Client.Client({{
             }
         }
 
-        self.create_contract_template(name, contract_id)?;
+        self.create_contract_template(name, contract_id, network_override, allow_http)?;
         Ok(())
     }
 
@@ -470,29 +820,7 @@ export default new Client.Client({{
             match generate_cmd.run(&args).await {
                 Err(e) if e.to_string().contains("already exists") => {
                     printer.blankln(e);
-                    // Check if account exists on chain
-                    let rpc_client = soroban_rpc::Client::new(
-                        network
-                            .rpc_url
-                            .as_ref()
-                            .expect("network contains the RPC url"),
-                    )?;
-
-                    let public_key_cmd = cli::keys::public_key::Cmd {
-                        name: account.name.clone().parse()?,
-                        locator: self.get_config_locator(),
-                        hd_path: None,
-                    };
-                    let address = public_key_cmd.public_key().await?;
-
-                    if (rpc_client.get_account(&address.to_string()).await).is_err() {
-                        printer.infoln("Account not found on chain, funding...");
-                        let fund_cmd = cli::keys::fund::Cmd {
-                            network: Self::get_network_args(network),
-                            address: public_key_cmd,
-                        };
-                        fund_cmd.run(&args).await?;
-                    }
+                    self.fund_account_if_missing(&account.name, network).await?;
                 }
                 other_result => other_result?,
             }
@@ -502,6 +830,65 @@ export default new Client.Client({{
         Ok(())
     }
 
+    /// Fund an existing account on the given network if it does not exist on chain yet
+    async fn fund_account_if_missing(&self, name: &str, network: &Network) -> Result<(), Error> {
+        let printer = self.printer();
+        let args =
+            self.global_args
+                .clone()
+                .unwrap_or_else(|| stellar_cli::commands::global::Args {
+                    locator: self.get_config_locator(),
+                    ..Default::default()
+                });
+        // Check if account exists on chain
+        let rpc_client = soroban_rpc::Client::new(&self.resolve_network(network)?.rpc_url)?;
+
+        let public_key_cmd = cli::keys::public_key::Cmd {
+            name: name.parse()?,
+            locator: self.get_config_locator(),
+            hd_path: None,
+        };
+        let address = public_key_cmd.public_key().await?;
+
+        if (rpc_client.get_account(&address.to_string()).await).is_err() {
+            printer.infoln("Account not found on chain, funding...");
+            let fund_cmd = cli::keys::fund::Cmd {
+                network: Self::get_network_args(network),
+                address: public_key_cmd,
+            };
+            fund_cmd.run(&args).await?;
+        }
+        Ok(())
+    }
+
+    /// Make sure the environment's accounts exist on each network that a contract to be deployed
+    /// overrides the environment's network with
+    async fn handle_network_overrides(
+        &self,
+        accounts: Option<&[env_toml::Account]>,
+        contracts: Option<&IndexMap<Box<str>, env_toml::Contract>>,
+    ) -> Result<(), Error> {
+        let printer = self.printer();
+        let mut funded = vec![Self::network_passphrase()?];
+        let networks = contracts
+            .into_iter()
+            .flat_map(|contracts| contracts.values())
+            .filter(|settings| settings.client && settings.id.is_none())
+            .filter_map(|settings| settings.network.as_ref());
+        for network in networks {
+            let passphrase = self.resolve_network(network)?.network_passphrase;
+            if funded.contains(&passphrase) {
+                continue;
+            }
+            printer.infoln(format!("Checking accounts on network {passphrase:?}"));
+            for account in accounts.unwrap_or_default() {
+                self.fund_account_if_missing(&account.name, network).await?;
+            }
+            funded.push(passphrase);
+        }
+        Ok(())
+    }
+
     fn maintain_user_ordering(
         package_names: &[String],
         contracts: Option<&IndexMap<Box<str>, env_toml::Contract>>,
@@ -534,6 +921,7 @@ export default new Client.Client({{
     async fn handle_production_contracts(
         &self,
         contracts: &IndexMap<Box<str>, env_toml::Contract>,
+        allow_http: bool,
     ) -> Result<(), Error> {
         for (name, contract) in contracts.iter().filter(|(_, settings)| settings.client) {
             if let Some(id) = &contract.id {
@@ -541,7 +929,12 @@ export default new Client.Client({{
                     return Err(Error::InvalidContractID(id.to_string()));
                 }
                 self.clone()
-                    .generate_contract_bindings(name, &id.to_string())
+                    .generate_contract_bindings(
+                        name,
+                        &id.to_string(),
+                        contract.network.as_ref(),
+                        allow_http,
+                    )
                     .await?;
             } else {
                 return Err(Error::MissingContractID(name.to_string()));
@@ -552,55 +945,166 @@ export default new Client.Client({{
 
     async fn handle_contracts(
         &self,
-        contracts: Option<&IndexMap<Box<str>, env_toml::Contract>>,
+        current_env: &env_toml::Environment,
         package_names: Vec<String>,
-        network: &Network,
     ) -> Result<(), Error> {
         let printer = self.printer();
         if package_names.is_empty() {
             return Ok(());
         }
 
-        let env = self.stellar_scaffold_env(ScaffoldEnv::Production);
-        if env == "production" || env == "staging" {
+        let contracts = current_env.contracts.as_ref();
+        if current_env.mode == Mode::PinnedIds {
             if let Some(contracts) = contracts {
-                self.handle_production_contracts(contracts).await?;
+                self.handle_production_contracts(contracts, current_env.allow_http)
+                    .await?;
             }
             return Ok(());
         }
 
+        let env = self.stellar_scaffold_env();
+
         self.validate_contract_names(contracts)?;
 
         let names = Self::maintain_user_ordering(&package_names, contracts);
 
-        let mut results: Vec<(String, Result<(), String>)> = Vec::new();
-
-        for name in names {
-            let settings = contracts
-                .and_then(|contracts| contracts.get(name.as_str()))
-                .cloned()
-                .unwrap_or_default();
+        let workspace_root = self
+            .workspace_root
+            .as_ref()
+            .expect("workspace_root not set");
+        let locked = Lockfile::load(workspace_root)?;
+        let mut lockfile = locked.clone();
 
-            // Skip if client generation is disabled
-            if !settings.client {
-                continue;
+        // Skip contracts with client generation disabled
+        let names = names
+            .into_iter()
+            .filter(|name| Self::contract_settings(contracts, name).client)
+            .collect::<Vec<_>>();
+        // Deployments are locked under the network each contract is deployed to
+        let passphrases = names
+            .iter()
+            .map(|name| self.contract_network_passphrase(&Self::contract_settings(contracts, name)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let dependencies = self.deploy_dependencies(&names, contracts)?;
+
+        // Independent contracts are processed concurrently, up to `jobs` at a time. Each
+        // transaction holds the lock of its source account in `transactions` while it is sent,
+        // since transactions from one account share its sequence number.
+        let this = Arc::new(self.clone());
+        let shared_env = Arc::new(current_env.clone());
+        let transactions = Arc::new(SourceLocks::new(self.get_config_locator()));
+        // IDs of pinned contracts and of the contracts processed so far, for
+        // `${contract:<name>}` references
+        let mut contract_ids = contracts
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, settings)| Some((name.to_string(), settings.id.clone()?)))
+            .collect::<HashMap<_, _>>();
+        let mut results: Vec<Option<Result<(), String>>> = names.iter().map(|_| None).collect();
+        // Recorded even for contracts that failed, e.g. after some of their after_deploy steps
+        let mut deployments: Vec<Option<Deployment>> = names.iter().map(|_| None).collect();
+        let mut pending = (0..names.len()).collect::<Vec<_>>();
+        let mut running = JoinSet::new();
+
+        loop {
+            // Contracts whose dependencies failed are skipped, which in turn skips their own
+            // dependents
+            while let Some((position, dep)) =
+                pending
+                    .iter()
+                    .enumerate()
+                    .find_map(|(position, &i)| {
+                        match Self::dependency_status(&dependencies, &names[i], &names, &results) {
+                            DependencyStatus::Failed(dep) => Some((position, dep)),
+                            DependencyStatus::Done | DependencyStatus::Pending => None,
+                        }
+                    })
+            {
+                let i = pending.remove(position);
+                printer.errorln(format!("Skipping {:?} because {dep:?} failed", names[i]));
+                results[i] = Some(Err(format!("skipped because {dep:?} failed")));
+            }
+            while running.len() < usize::from(self.jobs) {
+                // Start the first contract, in user order, whose dependencies are done
+                let Some(position) = pending.iter().position(|&i| {
+                    matches!(
+                        Self::dependency_status(&dependencies, &names[i], &names, &results),
+                        DependencyStatus::Done
+                    )
+                }) else {
+                    break;
+                };
+                let i = pending.remove(position);
+                let name = names[i].clone();
+                let settings = Self::contract_settings(contracts, &name);
+                let locked = locked.get(&env, &passphrases[i], &name).cloned();
+                let (this, current_env, transactions, contract_ids) = (
+                    Arc::clone(&this),
+                    Arc::clone(&shared_env),
+                    Arc::clone(&transactions),
+                    contract_ids.clone(),
+                );
+                running.spawn(async move {
+                    let mut deployment = None;
+                    let result = this
+                        .process_single_contract(
+                            &name,
+                            settings,
+                            &current_env,
+                            locked.as_ref(),
+                            &transactions,
+                            &contract_ids,
+                            &mut deployment,
+                        )
+                        .await;
+                    (i, deployment, result)
+                });
             }
 
-            match self
-                .process_single_contract(&name, settings, network, &env)
-                .await
-            {
-                Ok(()) => {
+            let Some(finished) = running.join_next().await else {
+                break;
+            };
+            let (i, deployment, result) = finished?;
+            let name = &names[i];
+            deployments[i] = deployment;
+            results[i] = Some(match result {
+                Ok(contract_id) => {
                     printer.checkln(format!("Successfully generated client for: {name}"));
-                    results.push((name, Ok(())));
+                    contract_ids.insert(name.clone(), contract_id.to_string());
+                    Ok(())
                 }
                 Err(e) => {
                     printer.errorln(format!("Failed to generate client for: {name}"));
-                    results.push((name, Err(e.to_string())));
+                    Err(e.to_string())
                 }
+            });
+        }
+
+        // Results are collected in user order so the lockfile and summary do not depend on
+        // which contract finished first
+        for ((name, deployment), passphrase) in names.iter().zip(deployments).zip(&passphrases) {
+            if let Some(deployment) = deployment {
+                lockfile.insert(&env, passphrase, name, deployment);
             }
         }
+        if lockfile != locked {
+            lockfile.save(workspace_root)?;
+        }
+        let results = names
+            .into_iter()
+            .zip(results)
+            .map(|(name, result)| match result {
+                Some(result) => Ok((name, result)),
+                None => Err(Error::ContractNotProcessed(name)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.print_summary(results);
+        Ok(())
+    }
 
+    fn print_summary(&self, results: Vec<(String, Result<(), String>)>) {
+        let printer = self.printer();
         // Partition results into successes and failures
         let (successes, failures): (Vec<_>, Vec<_>) =
             results.into_iter().partition(|(_, result)| result.is_ok());
@@ -618,8 +1122,82 @@ export default new Client.Client({{
                 }
             }
         }
+    }
 
-        Ok(())
+    fn contract_settings(
+        contracts: Option<&IndexMap<Box<str>, env_toml::Contract>>,
+        name: &str,
+    ) -> env_toml::Contract {
+        contracts
+            .and_then(|contracts| contracts.get(name))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Contracts that each contract has to wait for: the workspace contracts it depends on in
+    /// Cargo.toml, and the contracts its `constructor_args` and `after_deploy` reference with
+    /// `${contract:<name>}`. Errors if the references form a cycle.
+    fn deploy_dependencies(
+        &self,
+        names: &[String],
+        contracts: Option<&IndexMap<Box<str>, env_toml::Contract>>,
+    ) -> Result<HashMap<String, Vec<String>>, Error> {
+        let mut dependencies = self.contract_dependencies.clone();
+        for name in names {
+            let settings = Self::contract_settings(contracts, name);
+            let scripts = [
+                settings.constructor_args.clone(),
+                settings.after_deploy.as_ref().map(AfterDeploy::source),
+            ];
+            for script in scripts.iter().flatten() {
+                for reference in interpolate::references(script)? {
+                    let Reference::Contract(dep) = reference else {
+                        continue;
+                    };
+                    // after_deploy can refer to the contract it runs for
+                    if dep == name {
+                        continue;
+                    }
+                    if names.iter().any(|name| name == dep) {
+                        dependencies
+                            .entry(name.clone())
+                            .or_default()
+                            .push(dep.to_string());
+                    } else if Self::contract_settings(contracts, dep).id.is_none() {
+                        return Err(Error::UnknownContractReference(
+                            name.clone(),
+                            dep.to_string(),
+                        ));
+                    }
+                }
+            }
+        }
+        if let Some(cycle) = find_cycle(&dependencies, names) {
+            return Err(Error::DeployCycle(cycle.join(" -> ")));
+        }
+        Ok(dependencies)
+    }
+
+    /// Progress of the contracts that `name` depends on, among those being processed
+    fn dependency_status(
+        dependencies: &HashMap<String, Vec<String>>,
+        name: &str,
+        names: &[String],
+        results: &[Option<Result<(), String>>],
+    ) -> DependencyStatus {
+        let mut status = DependencyStatus::Done;
+        for dep in dependencies.get(name).into_iter().flatten() {
+            match names
+                .iter()
+                .position(|name| name == dep)
+                .map(|i| &results[i])
+            {
+                Some(Some(Err(_))) => return DependencyStatus::Failed(dep.clone()),
+                Some(None) => status = DependencyStatus::Pending,
+                Some(Some(Ok(()))) | None => {}
+            }
+        }
+        status
     }
 
     fn get_wasm_path(&self, contract_name: &str) -> std::path::PathBuf {
@@ -651,35 +1229,211 @@ export default new Client.Client({{
         Ok(())
     }
 
+    /// Deploy or upgrade a contract if needed, run its pending `after_deploy` steps and generate
+    /// its client. `deployment` is updated as each step completes, so progress is recorded even
+    /// if a later step fails.
+    #[allow(clippy::too_many_arguments)]
     async fn process_single_contract(
         &self,
         name: &str,
         settings: env_toml::Contract,
-        network: &Network,
-        env: &str,
-    ) -> Result<(), Error> {
+        current_env: &env_toml::Environment,
+        locked: Option<&Deployment>,
+        transactions: &SourceLocks,
+        contract_ids: &HashMap<String, String>,
+        deployment: &mut Option<Deployment>,
+    ) -> Result<Contract, Error> {
         let printer = self.printer();
+        let network_override = settings.network.clone();
+        let network = network_override.as_ref().unwrap_or(&current_env.network);
         // First check if we have an ID in settings
-        let contract_id = if let Some(id) = &settings.id {
-            Contract::from_string(id).map_err(|_| Error::InvalidContractID(id.clone()))?
-        } else {
-            let wasm_path = self.get_wasm_path(name);
-            if !wasm_path.exists() {
-                return Err(Error::BadContractName(name.to_string()));
+        if let Some(id) = &settings.id {
+            let contract_id =
+                Contract::from_string(id).map_err(|_| Error::InvalidContractID(id.clone()))?;
+            self.generate_contract_bindings(
+                name,
+                &contract_id.to_string(),
+                network_override.as_ref(),
+                current_env.allow_http,
+            )
+            .await?;
+            return Ok(contract_id);
+        }
+
+        let wasm_path = self.get_wasm_path(name);
+        if !wasm_path.exists() {
+            return Err(Error::BadContractName(name.to_string()));
+        }
+        if current_env.run_after_deploy {
+            Self::validate_after_deploy(name, settings.after_deploy.as_ref(), &wasm_path)?;
+        }
+        let existing_contract_id = self.get_contract_alias(name, network)?;
+
+        // If the lockfile agrees with the local wasm, settings and alias, and the contract still
+        // exists, there is nothing to deploy
+        let lock_current = self
+            .lock_is_current(
+                name,
+                locked,
+                existing_contract_id.as_ref(),
+                &wasm_path,
+                &settings,
+                network,
+                contract_ids,
+            )
+            .await?;
+        let (contract_id, up_to_date) = match existing_contract_id {
+            Some(existing_contract_id) if lock_current => {
+                printer.checkln(format!("Contract {name:?} is up to date"));
+                *deployment = locked.cloned();
+                (existing_contract_id, true)
             }
-            let new_hash = self.upload_contract_wasm(name, &wasm_path).await?;
-            let mut upgraded_contract = None;
+            existing_contract_id => {
+                let (contract_id, deployed, up_to_date) = self
+                    .deploy_from_source(
+                        name,
+                        &settings,
+                        &wasm_path,
+                        existing_contract_id,
+                        locked,
+                        network,
+                        contract_ids,
+                        transactions,
+                    )
+                    .await?;
+                self.save_contract_alias(name, &contract_id, network)?;
+                *deployment = Some(deployed);
+                (contract_id, up_to_date)
+            }
+        };
+
+        // Run pending after_deploy steps if the environment enables them
+        if let (Some(after_deploy), true) = (&settings.after_deploy, current_env.run_after_deploy) {
+            let state = deployment.as_mut().expect("deployment is set above");
+            if self.rerun_after_deploy.iter().any(|rerun| rerun == name) {
+                state.after_deploy_done = Some(Vec::new());
+            }
+            let context = self
+                .settings_context(name, &contract_id, &settings, contract_ids)
+                .await?;
+            let keys = deployments::after_deploy_step_keys(after_deploy, &context)?;
+            if keys.iter().any(|key| !state.after_deploy_step_done(key)) {
+                printer.infoln(format!("Running after_deploy script for {name:?}"));
+                self.run_after_deploy(
+                    name,
+                    &contract_id,
+                    after_deploy,
+                    &keys,
+                    state,
+                    network,
+                    &context,
+                    transactions,
+                )
+                .await?;
+            }
+        }
 
-            // Check existing alias - if it exists and matches hash, we can return early
-            if let Some(existing_contract_id) = self.get_contract_alias(name)? {
-                let hash = self
-                    .get_contract_hash(&existing_contract_id, network)
+        if !up_to_date {
+            self.generate_contract_bindings(
+                name,
+                &contract_id.to_string(),
+                network_override.as_ref(),
+                current_env.allow_http,
+            )
+            .await?;
+        }
+
+        Ok(contract_id)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn lock_is_current(
+        &self,
+        name: &str,
+        locked: Option<&Deployment>,
+        existing_contract_id: Option<&Contract>,
+        wasm_path: &Path,
+        settings: &env_toml::Contract,
+        network: &Network,
+        contract_ids: &HashMap<String, String>,
+    ) -> Result<bool, Error> {
+        let (Some(locked), Some(existing_contract_id)) = (locked, existing_contract_id) else {
+            return Ok(false);
+        };
+        if self.refresh_deployments || locked.contract_id != existing_contract_id.to_string() {
+            return Ok(false);
+        }
+        let context = self
+            .settings_context(name, existing_contract_id, settings, contract_ids)
+            .await?;
+        if !locked.is_current(&deployments::wasm_hash(wasm_path)?, settings, &context)? {
+            return Ok(false);
+        }
+        // The lock can outlive the contract, e.g. after a network reset
+        self.contract_instance_exists(existing_contract_id, network)
+            .await
+    }
+
+    /// Upload the contract's wasm, then upgrade or (re)deploy the contract unless it already runs
+    /// that wasm. Returns the contract ID, its new lockfile entry and whether it was up to date.
+    #[allow(clippy::too_many_arguments)]
+    async fn deploy_from_source(
+        &self,
+        name: &str,
+        settings: &env_toml::Contract,
+        wasm_path: &Path,
+        existing_contract_id: Option<Contract>,
+        locked: Option<&Deployment>,
+        network: &Network,
+        contract_ids: &HashMap<String, String>,
+        transactions: &SourceLocks,
+    ) -> Result<(Contract, Deployment, bool), Error> {
+        let printer = self.printer();
+        let new_hash = self
+            .upload_contract_wasm(name, wasm_path, network, transactions)
+            .await?;
+        let mut upgraded_contract = None;
+
+        // Check existing alias - if it exists and matches hash, we can return early
+        if let Some(existing_contract_id) = existing_contract_id {
+            let hash = self
+                .get_contract_hash(&existing_contract_id, network)
+                .await?;
+            if let Some(current_hash) = hash {
+                let context = self
+                    .settings_context(name, &existing_contract_id, settings, contract_ids)
                     .await?;
-                if let Some(current_hash) = hash {
-                    if current_hash == new_hash {
-                        printer.checkln(format!("Contract {name:?} is up to date"));
-                        return Ok(());
-                    }
+                if let Some(locked) = locked.filter(|locked| locked.wasm_hash != current_hash) {
+                    printer.warnln(format!(
+                        "Contract {name:?} has drifted from {LOCK_FILE}: locked wasm hash {}, on-chain wasm hash {current_hash}",
+                        locked.wasm_hash
+                    ));
+                }
+                // The constructor only runs on deploy, so new constructor args need a new contract
+                let constructor_args_changed = match locked {
+                    Some(locked) => !locked.same_constructor_args(settings, &context)?,
+                    None => false,
+                };
+                if current_hash == new_hash && !constructor_args_changed {
+                    printer.checkln(format!("Contract {name:?} is up to date"));
+                    let mut deployment = Deployment::new(
+                        existing_contract_id.to_string(),
+                        new_hash,
+                        settings,
+                        &context,
+                    )?;
+                    // Steps already run for this contract stay done. Without a record of them,
+                    // as for contracts deployed before they were tracked, they all count as done.
+                    deployment.after_deploy_done = locked
+                        .filter(|locked| locked.contract_id == deployment.contract_id)
+                        .and_then(|locked| locked.after_deploy_done.clone());
+                    return Ok((existing_contract_id, deployment, true));
+                }
+                if constructor_args_changed {
+                    printer.infoln(format!(
+                        "constructor_args for {name:?} changed, it will be redeployed"
+                    ));
+                } else {
                     upgraded_contract = self
                         .try_upgrade_contract(
                             name,
@@ -687,40 +1441,91 @@ export default new Client.Client({{
                             &current_hash,
                             &new_hash,
                             network,
+                            transactions,
                         )
                         .await?;
                 }
-                printer.infoln(format!("Updating contract {name:?}"));
             }
+            printer.infoln(format!("Updating contract {name:?}"));
+        }
 
-            // Deploy new contract if we got here (don't deploy if we already run an upgrade)
-            let contract_id = if let Some(upgraded) = upgraded_contract {
-                upgraded
-            } else {
-                self.deploy_contract(name, &new_hash, &settings).await?
-            };
-            // Run after_deploy script if in development or test environment
-            if let Some(after_deploy) = settings.after_deploy.as_deref() {
-                if env == "development" || env == "testing" {
-                    printer.infoln(format!("Running after_deploy script for {name:?}"));
-                    self.run_after_deploy_script(name, &contract_id, after_deploy)
-                        .await?;
-                }
-            }
-            self.save_contract_alias(name, &contract_id, network)?;
-            contract_id
+        // Deploy new contract if we got here (don't deploy if we already run an upgrade)
+        let contract_id = if let Some(upgraded) = upgraded_contract {
+            upgraded
+        } else {
+            let context = self
+                .interpolation_context(settings.constructor_args.as_deref(), contract_ids)
+                .await?;
+            self.deploy_contract(name, &new_hash, settings, network, &context, transactions)
+                .await?
         };
-
-        self.generate_contract_bindings(name, &contract_id.to_string())
+        let context = self
+            .settings_context(name, &contract_id, settings, contract_ids)
             .await?;
+        let deployment = Deployment::new(contract_id.to_string(), new_hash, settings, &context)?;
+        Ok((contract_id, deployment, false))
+    }
 
-        Ok(())
+    /// Context for both `constructor_args` and `after_deploy` of the contract `name` deployed as
+    /// `contract_id`, which the lockfile digests are computed with
+    async fn settings_context(
+        &self,
+        name: &str,
+        contract_id: &Contract,
+        settings: &env_toml::Contract,
+        contract_ids: &HashMap<String, String>,
+    ) -> Result<interpolate::Context, Error> {
+        let mut contract_ids = contract_ids.clone();
+        contract_ids.insert(name.to_string(), contract_id.to_string());
+        let sources = [
+            settings.constructor_args.clone(),
+            settings.after_deploy.as_ref().map(AfterDeploy::source),
+        ];
+        let script = sources.into_iter().flatten().collect::<Vec<_>>().join("\n");
+        self.interpolation_context(Some(&script), &contract_ids)
+            .await
+    }
+
+    /// Look up the values of the `${account:...}` and `${contract:...}` references in `script`
+    async fn interpolation_context(
+        &self,
+        script: Option<&str>,
+        contract_ids: &HashMap<String, String>,
+    ) -> Result<interpolate::Context, Error> {
+        let mut context = interpolate::Context {
+            accounts: HashMap::new(),
+            contracts: contract_ids.clone(),
+        };
+        for reference in interpolate::references(script.unwrap_or_default())? {
+            let Reference::Account(name) = reference else {
+                continue;
+            };
+            if context.accounts.contains_key(name) {
+                continue;
+            }
+            let unknown_account = || interpolate::Error::UnknownAccount(name.to_string());
+            let public_key_cmd = cli::keys::public_key::Cmd {
+                name: name.parse().map_err(|_| unknown_account())?,
+                locator: self.get_config_locator(),
+                hd_path: None,
+            };
+            let address = public_key_cmd
+                .public_key()
+                .await
+                .map_err(|_| unknown_account())?;
+            context
+                .accounts
+                .insert(name.to_string(), address.to_string());
+        }
+        Ok(context)
     }
 
     async fn upload_contract_wasm(
         &self,
         name: &str,
         wasm_path: &std::path::Path,
+        network: &Network,
+        transactions: &SourceLocks,
     ) -> Result<String, Error> {
         let printer = self.printer();
         printer.infoln(format!("Installing {name:?} wasm bytecode on-chain..."));
@@ -728,26 +1533,38 @@ export default new Client.Client({{
             .workspace_root
             .as_ref()
             .expect("workspace_root must be set before running");
-        let hash = cli::contract::upload::Cmd::parse_arg_vec(&[
-            "--wasm",
+        let mut upload_args = vec![
+            "--wasm".to_string(),
             wasm_path
                 .to_str()
-                .expect("we do not support non-utf8 paths"),
-            "--config-dir",
+                .expect("we do not support non-utf8 paths")
+                .to_string(),
+            "--config-dir".to_string(),
             workspace_root
                 .to_str()
-                .expect("we do not support non-utf8 paths"),
-        ])?
-        .run_against_rpc_server(self.global_args.as_ref(), None)
-        .await?
-        .into_result()
-        .expect("no hash returned by 'contract upload'")
-        .to_string();
+                .expect("we do not support non-utf8 paths")
+                .to_string(),
+        ];
+        upload_args.extend(self.network_args(network)?);
+        let upload_arg_refs: Vec<&str> = upload_args
+            .iter()
+            .map(std::string::String::as_str)
+            .collect();
+        let _transaction = transactions.lock(None).await;
+        let hash = cli::contract::upload::Cmd::parse_arg_vec(&upload_arg_refs)?
+            .run_against_rpc_server(self.global_args.as_ref(), None)
+            .await?
+            .into_result()
+            .expect("no hash returned by 'contract upload'")
+            .to_string();
         printer.infoln(format!("    ↳ hash: {hash}"));
         Ok(hash)
     }
 
-    fn parse_script_line(line: &str) -> Result<(Option<String>, Vec<String>), Error> {
+    fn parse_script_line(
+        line: &str,
+        context: &interpolate::Context,
+    ) -> Result<(Option<String>, Vec<String>), Error> {
         let re = Regex::new(r"\$\((.*?)\)").expect("Invalid regex pattern");
         let (shell, flag) = if cfg!(windows) {
             ("cmd", "/C")
@@ -756,8 +1573,12 @@ export default new Client.Client({{
         };
 
         let resolved_line = Self::resolve_line(&re, line, shell, flag)?;
+        // `${...}` references are resolved after splitting, so their values are never re-split
         let parts = split(&resolved_line)
-            .ok_or_else(|| Error::ScriptParseFailure(resolved_line.to_string()))?;
+            .ok_or_else(|| Error::ScriptParseFailure(resolved_line.to_string()))?
+            .iter()
+            .map(|part| context.resolve(part))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let (source_account, command_parts): (Vec<_>, Vec<_>) = parts
             .iter()
@@ -781,6 +1602,9 @@ export default new Client.Client({{
         name: &str,
         hash: &str,
         settings: &env_toml::Contract,
+        network: &Network,
+        context: &interpolate::Context,
+        transactions: &SourceLocks,
     ) -> Result<Contract, Error> {
         let printer = self.printer();
         let workspace_root = self
@@ -798,12 +1622,15 @@ export default new Client.Client({{
                 .expect("we do not support non-utf8 paths")
                 .to_string(),
         ];
+        deploy_args.extend(self.network_args(network)?);
 
+        let mut source = None;
         if let Some(constructor_script) = &settings.constructor_args {
-            let (source_account, mut args) = Self::parse_script_line(constructor_script)?;
+            let (source_account, mut args) = Self::parse_script_line(constructor_script, context)?;
 
             if let Some(account) = source_account {
-                deploy_args.extend_from_slice(&["--source-account".to_string(), account]);
+                deploy_args.extend_from_slice(&["--source-account".to_string(), account.clone()]);
+                source = Some(account);
             }
 
             deploy_args.push("--".to_string());
@@ -815,11 +1642,13 @@ export default new Client.Client({{
             .iter()
             .map(std::string::String::as_str)
             .collect();
+        let transaction = transactions.lock(source.as_deref()).await;
         let contract_id = cli::contract::deploy::wasm::Cmd::parse_arg_vec(&deploy_arg_refs)?
             .run_against_rpc_server(self.global_args.as_ref(), None)
             .await?
             .into_result()
             .expect("no contract id returned by 'contract deploy'");
+        drop(transaction);
         printer.infoln(format!("    ↳ contract_id: {contract_id}"));
 
         Ok(contract_id)
@@ -832,6 +1661,7 @@ export default new Client.Client({{
         existing_hash: &str,
         hash: &str,
         network: &Network,
+        transactions: &SourceLocks,
     ) -> Result<Option<Contract>, Error> {
         let printer = self.printer();
         let existing_spec = fetch_contract_spec(existing_hash, network).await?;
@@ -849,14 +1679,10 @@ export default new Client.Client({{
             .infoln("Upgradable contract found, will use 'upgrade' function instead of redeploy");
 
         let existing_contract_id_str = existing_contract_id.to_string();
-        let mut redeploy_args = vec![
-            "--id",
-            existing_contract_id_str.as_str(),
-            "--",
-            "upgrade",
-            "--new_wasm_hash",
-            hash,
-        ];
+        let network_args = self.network_args(network)?;
+        let mut redeploy_args = vec!["--id", existing_contract_id_str.as_str()];
+        redeploy_args.extend(network_args.iter().map(String::as_str));
+        redeploy_args.extend(["--", "upgrade", "--new_wasm_hash", hash]);
 
         let invoke_cmd = if legacy_upgradeable {
             let upgrade_operator = ArgParser::get_upgrade_args(name).map_err(UpgradeArgsError)?;
@@ -867,11 +1693,13 @@ export default new Client.Client({{
             cli::contract::invoke::Cmd::parse_arg_vec(&redeploy_args)
         }?;
         printer.infoln(format!("Upgrading {name:?} smart contract"));
+        let transaction = transactions.lock(None).await;
         invoke_cmd
             .run_against_rpc_server(self.global_args.as_ref(), None)
             .await?
             .into_result()
             .expect("no result returned by 'contract invoke'");
+        drop(transaction);
         printer.infoln(format!("Contract upgraded: {existing_contract_id}"));
 
         Ok(Some(existing_contract_id))
@@ -924,20 +1752,138 @@ export default new Client.Client({{
         }
     }
 
-    async fn run_after_deploy_script(
+    /// Check `after_deploy` steps against the contract's spec, so that a typo fails before
+    /// anything is sent to the network
+    fn validate_after_deploy(
+        name: &str,
+        after_deploy: Option<&AfterDeploy>,
+        wasm_path: &Path,
+    ) -> Result<(), Error> {
+        let Some(AfterDeploy::Steps(steps)) = after_deploy else {
+            return Ok(());
+        };
+        let entries = Spec::new(&std::fs::read(wasm_path)?)?.spec;
+        let spec = soroban_spec_tools::Spec::new(&entries);
+        let invalid = |message: String| Error::InvalidAfterDeployStep(name.to_string(), message);
+        for step in steps {
+            let function = &step.function;
+            let func = spec
+                .find_function(function)
+                .map_err(|_| invalid(format!("the contract has no function {function:?}")))?;
+            let inputs = func
+                .inputs
+                .iter()
+                .map(|input| (input.name.to_utf8_string_lossy(), &input.type_))
+                .collect::<Vec<_>>();
+            for (arg, value) in step.arg_values() {
+                let Some((_, type_)) = inputs
+                    .iter()
+                    .find(|(input, _)| *input == arg.replace('-', "_"))
+                else {
+                    let expected = inputs
+                        .iter()
+                        .map(|(input, _)| input.as_str())
+                        .collect::<Vec<_>>();
+                    return Err(invalid(format!(
+                        "{function} has no argument {arg:?}; its arguments are {expected:?}"
+                    )));
+                };
+                // Other types can refer to aliases or keys, which are resolved by `invoke`
+                let checkable = matches!(
+                    type_,
+                    ScSpecTypeDef::Bool
+                        | ScSpecTypeDef::U32
+                        | ScSpecTypeDef::I32
+                        | ScSpecTypeDef::U64
+                        | ScSpecTypeDef::I64
+                        | ScSpecTypeDef::U128
+                        | ScSpecTypeDef::I128
+                        | ScSpecTypeDef::U256
+                        | ScSpecTypeDef::I256
+                        | ScSpecTypeDef::String
+                        | ScSpecTypeDef::Symbol
+                );
+                if checkable && !value.contains("${") {
+                    spec.from_string(&value, type_).map_err(|e| {
+                        invalid(format!(
+                            "invalid value {value:?} for {function} --{arg}: {e}"
+                        ))
+                    })?;
+                }
+            }
+            for (input, type_) in &inputs {
+                let given = step.args.keys().any(|arg| arg.replace('-', "_") == *input);
+                if !given && !matches!(type_, ScSpecTypeDef::Option(_)) {
+                    return Err(invalid(format!("{function} is missing argument {input:?}")));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn step_invocation(
+        step: &AfterDeployStep,
+        context: &interpolate::Context,
+    ) -> Result<Invocation, Error> {
+        Ok(Invocation {
+            source: step
+                .source
+                .as_deref()
+                .map(|source| context.resolve(source))
+                .transpose()?,
+            send: step.send,
+            args: step
+                .invoke_args()
+                .iter()
+                .map(|arg| context.resolve(arg))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn run_after_deploy(
         &self,
         name: &str,
         contract_id: &Contract,
-        after_deploy_script: &str,
+        after_deploy: &AfterDeploy,
+        keys: &[String],
+        state: &mut Deployment,
+        network: &Network,
+        context: &interpolate::Context,
+        transactions: &SourceLocks,
     ) -> Result<(), Error> {
         let printer = self.printer();
-        for line in after_deploy_script.lines() {
-            let line = line.trim();
-            if line.is_empty() {
+        let network_args = self.network_args(network)?;
+        let steps: Vec<Either<&str, &AfterDeployStep>> = match after_deploy {
+            AfterDeploy::Script(script) => script
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(Either::Left)
+                .collect(),
+            AfterDeploy::Steps(steps) => steps.iter().map(Either::Right).collect(),
+        };
+        for (step, key) in steps.into_iter().zip(keys) {
+            if state.after_deploy_step_done(key) {
                 continue;
             }
-
-            let (source_account, command_parts) = Self::parse_script_line(line)?;
+            // Script lines are parsed one at a time, so `$(...)` runs after the previous invocation
+            let Invocation {
+                source: source_account,
+                send,
+                args: command_parts,
+            } = match step {
+                Either::Left(line) => {
+                    let (source, args) = Self::parse_script_line(line, context)?;
+                    Invocation {
+                        source,
+                        send: None,
+                        args,
+                    }
+                }
+                Either::Right(step) => Self::step_invocation(step, context)?,
+            };
+            let send_arg = send.map(|send| format!("--send={send}"));
 
             let workspace_root = self
                 .workspace_root
@@ -952,9 +1898,13 @@ export default new Client.Client({{
                     .to_str()
                     .expect("we do not support non-utf8 paths"),
             ];
+            args.extend(network_args.iter().map(String::as_str));
             if let Some(account) = source_account.as_ref() {
                 args.extend_from_slice(&["--source-account", account]);
             }
+            if let Some(send_arg) = send_arg.as_deref() {
+                args.push(send_arg);
+            }
             args.extend_from_slice(&["--"]);
             args.extend(command_parts.iter().map(std::string::String::as_str));
 
@@ -962,10 +1912,13 @@ export default new Client.Client({{
                 "  ↳ Executing: stellar contract invoke {}",
                 args.join(" ")
             ));
+            let transaction = transactions.lock(source_account.as_deref()).await;
             let result = cli::contract::invoke::Cmd::parse_arg_vec(&args)?
                 .run_against_rpc_server(self.global_args.as_ref(), None)
                 .await?;
+            drop(transaction);
             printer.infoln(format!("  ↳ Result: {result:?}"));
+            state.record_after_deploy_step(key);
         }
         printer.checkln(format!(
             "After deploy script for {name:?} completed successfully"
@@ -974,6 +1927,105 @@ export default new Client.Client({{
     }
 }
 
+/// Find a cycle in `dependencies` reachable from `names`, returned as the path that closes it
+fn find_cycle(
+    dependencies: &HashMap<String, Vec<String>>,
+    names: &[String],
+) -> Option<Vec<String>> {
+    fn visit<'a>(
+        name: &'a str,
+        dependencies: &'a HashMap<String, Vec<String>>,
+        path: &mut Vec<&'a str>,
+        done: &mut Vec<&'a str>,
+    ) -> Option<Vec<String>> {
+        if let Some(start) = path.iter().position(|visiting| *visiting == name) {
+            let mut cycle = path[start..]
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            cycle.push(name.to_string());
+            return Some(cycle);
+        }
+        if done.contains(&name) {
+            return None;
+        }
+        path.push(name);
+        for dep in dependencies.get(name).into_iter().flatten() {
+            if let Some(cycle) = visit(dep, dependencies, path, done) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        done.push(name);
+        None
+    }
+
+    let mut done = Vec::new();
+    names
+        .iter()
+        .find_map(|name| visit(name, dependencies, &mut Vec::new(), &mut done))
+}
+
+/// Progress of the contracts a contract depends on
+enum DependencyStatus {
+    /// Every dependency was processed successfully
+    Done,
+    /// A dependency is still being processed
+    Pending,
+    /// This dependency failed or was skipped
+    Failed(String),
+}
+
+/// A lock per source account. Transactions from one account share its sequence number, so they
+/// are sent one at a time, while transactions from other accounts and client generation overlap.
+struct SourceLocks {
+    /// Resolves key names to public keys
+    locator: stellar_cli::config::locator::Args,
+    /// Locks keyed by the public key of the source account
+    locks: std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+impl SourceLocks {
+    fn new(locator: stellar_cli::config::locator::Args) -> Self {
+        Self {
+            locator,
+            locks: std::sync::Mutex::default(),
+        }
+    }
+
+    /// Wait until no other transaction is being sent from `source`, or from the default source
+    /// account if it is `None`. Key names and addresses of the same account share a lock.
+    async fn lock(&self, source: Option<&str>) -> OwnedMutexGuard<()> {
+        let source = source.map_or_else(
+            || std::env::var("STELLAR_ACCOUNT").unwrap_or_default(),
+            ToString::to_string,
+        );
+        let account = self.public_key(&source).await.unwrap_or(source);
+        let lock = Arc::clone(self.locks.lock().unwrap().entry(account).or_default());
+        lock.lock_owned().await
+    }
+
+    /// Public key of a key name, address or secret key. `None` if it can't be resolved, in
+    /// which case sending the transaction fails anyway.
+    async fn public_key(&self, source: &str) -> Option<String> {
+        let public_key_cmd = cli::keys::public_key::Cmd {
+            name: source.parse().ok()?,
+            locator: self.locator.clone(),
+            hd_path: None,
+        };
+        let address = public_key_cmd.public_key().await.ok()?;
+        Some(address.to_string())
+    }
+}
+
+/// A `stellar contract invoke` call from an `after_deploy` setting
+struct Invocation {
+    source: Option<String>,
+    send: Option<SendMode>,
+    /// Function name and arguments, passed after `--`
+    args: Vec<String>,
+}
+
 async fn fetch_contract_spec(
     wasm_hash: &str,
     network: &Network,
@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::{env_toml, interpolate};
+
+pub const LOCK_FILE: &str = "deployments.lock";
+
+const HEADER: &str =
+    "# This file is generated by stellar-scaffold and records the on-chain state of\n\
+# each contract deployed by `stellar-scaffold build --build-clients`.\n\
+# It is meant to be checked in; do not edit it manually.\n\n";
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("⛔ ️reading {0:?}: {1}")]
+    Reading(PathBuf, std::io::Error),
+    #[error("⛔ ️parsing {0:?}: {1}")]
+    Parsing(PathBuf, toml::de::Error),
+    #[error("⛔ ️writing {0:?}: {1}")]
+    Writing(PathBuf, std::io::Error),
+    #[error("⛔ ️serializing deployments.lock: {0}")]
+    Serializing(#[from] toml::ser::Error),
+}
+
+/// Contracts deployed under a single network passphrase, keyed by contract alias
+pub type Deployments = BTreeMap<String, Deployment>;
+
+/// Contents of `.stellar/deployments.lock`, keyed by environment name and then by network
+/// passphrase. `BTreeMap`s keep the serialized output stable so diffs only show real changes.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Lockfile {
+    environments: BTreeMap<String, BTreeMap<String, Deployments>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deployment {
+    pub contract_id: String,
+    pub wasm_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constructor_args_digest: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_deploy_digest: Option<String>,
+    /// Keys of the `after_deploy` steps that completed for this contract ID, see
+    /// [`after_deploy_step_keys`]. `None` for entries recorded before steps were tracked, whose
+    /// steps all count as completed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_deploy_done: Option<Vec<String>>,
+}
+
+impl Lockfile {
+    pub fn path(workspace_root: &Path) -> PathBuf {
+        workspace_root.join(".stellar").join(LOCK_FILE)
+    }
+
+    /// Load the lockfile from the workspace, returning an empty one if it does not exist yet
+    pub fn load(workspace_root: &Path) -> Result<Self, Error> {
+        let path = Self::path(workspace_root);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents =
+            std::fs::read_to_string(&path).map_err(|e| Error::Reading(path.clone(), e))?;
+        toml::from_str(&contents).map_err(|e| Error::Parsing(path, e))
+    }
+
+    pub fn save(&self, workspace_root: &Path) -> Result<(), Error> {
+        let path = Self::path(workspace_root);
+        let contents = format!("{HEADER}{}", toml::to_string(self)?);
+        std::fs::write(&path, contents).map_err(|e| Error::Writing(path, e))
+    }
+
+    pub fn get(&self, env: &str, network_passphrase: &str, alias: &str) -> Option<&Deployment> {
+        self.environments
+            .get(env)
+            .and_then(|networks| networks.get(network_passphrase))
+            .and_then(|deployments| deployments.get(alias))
+    }
+
+    pub fn insert(
+        &mut self,
+        env: &str,
+        network_passphrase: &str,
+        alias: &str,
+        deployment: Deployment,
+    ) {
+        self.environments
+            .entry(env.to_string())
+            .or_default()
+            .entry(network_passphrase.to_string())
+            .or_default()
+            .insert(alias.to_string(), deployment);
+    }
+}
+
+impl Deployment {
+    /// Record `settings` with their `${...}` references resolved by `context`, which also has
+    /// this contract's ID
+    pub fn new(
+        contract_id: String,
+        wasm_hash: String,
+        settings: &env_toml::Contract,
+        context: &interpolate::Context,
+    ) -> Result<Self, interpolate::Error> {
+        Ok(Self {
+            contract_id,
+            wasm_hash,
+            constructor_args_digest: constructor_args_digest(settings, context)?,
+            after_deploy_digest: after_deploy_digest(settings, context)?,
+            after_deploy_done: Some(Vec::new()),
+        })
+    }
+
+    /// Whether the `after_deploy` step with the given key has completed for this contract
+    pub fn after_deploy_step_done(&self, key: &str) -> bool {
+        self.after_deploy_done
+            .as_ref()
+            .is_none_or(|done| done.iter().any(|done| done == key))
+    }
+
+    /// Record that the `after_deploy` step with the given key completed
+    pub fn record_after_deploy_step(&mut self, key: &str) {
+        let done = self.after_deploy_done.get_or_insert_with(Vec::new);
+        if !done.iter().any(|done| done == key) {
+            done.push(key.to_string());
+        }
+    }
+
+    /// Whether the contract was deployed with the same constructor args once resolved, so a
+    /// changed variable, key or contract they reference counts as a change
+    pub fn same_constructor_args(
+        &self,
+        settings: &env_toml::Contract,
+        context: &interpolate::Context,
+    ) -> Result<bool, interpolate::Error> {
+        Ok(self.constructor_args_digest == constructor_args_digest(settings, context)?)
+    }
+
+    /// Whether the locked state matches the given wasm hash and resolved contract settings
+    /// exactly, in which case nothing needs to be sent to the network
+    pub fn is_current(
+        &self,
+        wasm_hash: &str,
+        settings: &env_toml::Contract,
+        context: &interpolate::Context,
+    ) -> Result<bool, interpolate::Error> {
+        Ok(self.wasm_hash == wasm_hash
+            && self.same_constructor_args(settings, context)?
+            && self.after_deploy_digest == after_deploy_digest(settings, context)?)
+    }
+}
+
+fn constructor_args_digest(
+    settings: &env_toml::Contract,
+    context: &interpolate::Context,
+) -> Result<Option<String>, interpolate::Error> {
+    settings
+        .constructor_args
+        .as_deref()
+        .map(|args| Ok(digest(&context.resolve(args)?)))
+        .transpose()
+}
+
+fn after_deploy_digest(
+    settings: &env_toml::Contract,
+    context: &interpolate::Context,
+) -> Result<Option<String>, interpolate::Error> {
+    settings
+        .after_deploy
+        .as_ref()
+        .map(|after_deploy| Ok(digest(&context.resolve(&after_deploy.source())?)))
+        .transpose()
+}
+
+/// Keys identifying each step of an `after_deploy` setting, in order. A step's key is derived
+/// from its resolved text and how many identical steps precede it, so editing one step or a
+/// value it references only reruns that step, while repeating a step runs it again.
+pub fn after_deploy_step_keys(
+    after_deploy: &env_toml::AfterDeploy,
+    context: &interpolate::Context,
+) -> Result<Vec<String>, interpolate::Error> {
+    let steps = after_deploy
+        .step_sources()
+        .iter()
+        .map(|step| context.resolve(step))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let occurrence = steps[..i].iter().filter(|prev| *prev == step).count();
+            digest(&format!("{occurrence}:{step}"))
+        })
+        .collect())
+}
+
+/// Hex-encoded SHA-256 of a configuration string
+pub fn digest(s: &str) -> String {
+    hex::encode(Sha256::digest(s.trim().as_bytes()))
+}
+
+/// Hex-encoded SHA-256 of a wasm file, the same hash the network assigns to uploaded wasm
+pub fn wasm_hash(wasm_path: &Path) -> Result<String, std::io::Error> {
+    Ok(hex::encode(Sha256::digest(std::fs::read(wasm_path)?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn referenced_values_are_part_of_the_digests() {
+        let settings = env_toml::Contract {
+            constructor_args: Some("--admin ${contract:admin}".to_string()),
+            after_deploy: Some(env_toml::AfterDeploy::Script(
+                "init --admin ${contract:admin}".to_string(),
+            )),
+            ..Default::default()
+        };
+        let context = |admin: &str| interpolate::Context {
+            contracts: [("admin".to_string(), admin.to_string())].into(),
+            ..Default::default()
+        };
+        let deployment = Deployment::new(
+            "C1".to_string(),
+            "hash".to_string(),
+            &settings,
+            &context("CA"),
+        )
+        .unwrap();
+
+        assert!(deployment
+            .is_current("hash", &settings, &context("CA"))
+            .unwrap());
+        assert!(!deployment
+            .same_constructor_args(&settings, &context("CB"))
+            .unwrap());
+        assert!(!deployment
+            .is_current("hash", &settings, &context("CB"))
+            .unwrap());
+        let after_deploy = settings.after_deploy.as_ref().unwrap();
+        assert_ne!(
+            after_deploy_step_keys(after_deploy, &context("CA")).unwrap(),
+            after_deploy_step_keys(after_deploy, &context("CB")).unwrap()
+        );
+    }
+}
@@ -1,11 +1,17 @@
 use indexmap::IndexMap;
 use serde::Deserialize;
-use std::collections::BTreeMap as Map;
 use std::path::Path;
 use toml::value::Table;
+use toml::Value;
 
 pub const ENV_FILE: &str = "environments.toml";
 
+/// Table whose settings every other environment inherits, unless it sets `extends`
+pub const DEFAULT_ENV: &str = "default";
+
+/// Keys that identify a network. If an environment sets any of them, none of them are inherited.
+const NETWORK_IDENTITY: [&str; 3] = ["name", "rpc-url", "network-passphrase"];
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("⛔ ️parsing environments.toml: {0}")]
@@ -14,15 +20,41 @@ pub enum Error {
     NoSettingsForCurrentEnv(String),
     #[error("⛔ ️reading environments.toml as a string: {0}")]
     ParsingString(#[from] std::io::Error),
+    #[error("⛔ ️{0:?} in environments.toml must be a table")]
+    NotATable(String),
+    #[error("⛔ ️extends in environment {0:?} must be the name of another environment")]
+    InvalidExtends(String),
+    #[error("⛔ ️environment {0:?} extends {1:?}, which is not defined in environments.toml")]
+    UnknownParent(String, String),
+    #[error("⛔ ️environments in environments.toml extend each other in a cycle: {0}")]
+    ExtendsCycle(String),
+    #[error("⛔ ️{0:?} is not a valid environment name; [default] holds the settings shared by all environments")]
+    InvalidEnvName(String),
 }
 
-type Environments = Map<Box<str>, Environment>;
-
 #[derive(Debug, Clone)]
 pub struct Environment {
     pub accounts: Option<Vec<Account>>,
     pub network: Network,
     pub contracts: Option<IndexMap<Box<str>, Contract>>,
+    pub mode: Mode,
+    /// Whether generated clients may connect to the RPC server over plain HTTP
+    pub allow_http: bool,
+    /// Whether `after_deploy` scripts run after a contract is deployed or upgraded
+    pub run_after_deploy: bool,
+    /// Whether to start a local network in docker before building
+    pub run_locally: bool,
+}
+
+/// How contracts get their IDs in an environment
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Mode {
+    /// Build, upload and deploy contracts from the workspace
+    #[default]
+    DeployFromSource,
+    /// Only generate clients for contracts whose `id` is set in environments.toml
+    PinnedIds,
 }
 
 fn deserialize_accounts<'de, D>(deserializer: D) -> Result<Option<Vec<Account>>, D::Error>
@@ -44,6 +76,14 @@ impl<'de> Deserialize<'de> for Environment {
             accounts: Option<Vec<Account>>,
             network: Network,
             contracts: Option<Table>,
+            #[serde(default)]
+            mode: Mode,
+            #[serde(default)]
+            allow_http: bool,
+            #[serde(default)]
+            run_after_deploy: bool,
+            #[serde(default)]
+            run_locally: bool,
         }
 
         let helper = EnvironmentHelper::deserialize(deserializer)?;
@@ -66,6 +106,10 @@ impl<'de> Deserialize<'de> for Environment {
             accounts: helper.accounts,
             network: helper.network,
             contracts,
+            mode: helper.mode,
+            allow_http: helper.allow_http,
+            run_after_deploy: helper.run_after_deploy,
+            run_locally: helper.run_locally,
         })
     }
 }
@@ -112,14 +156,161 @@ pub struct Contract {
     #[serde(default = "default_client", skip_serializing_if = "std::ops::Not::not")]
     pub client: bool,
 
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub after_deploy: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_after_deploy",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub after_deploy: Option<AfterDeploy>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub constructor_args: Option<String>,
+
+    /// Deploy this contract to a different network than the rest of the environment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<Network>,
+}
+
+/// Contract invocations to run after a contract is deployed
+#[derive(Debug, Clone)]
+pub enum AfterDeploy {
+    /// One invocation per line, e.g. `STELLAR_ACCOUNT=admin mint --to admin --amount 100`
+    Script(String),
+    /// `[[<env>.contracts.<name>.after_deploy]]` tables
+    Steps(Vec<AfterDeployStep>),
+}
+
+#[derive(Debug, Clone, serde::Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AfterDeployStep {
+    /// Contract function to invoke
+    #[serde(rename = "fn")]
+    pub function: String,
+    /// Function arguments by name. Tables and arrays are passed as JSON.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub args: IndexMap<String, Value>,
+    /// Account to sign and pay for the invocation, instead of the default account
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Whether to send the transaction, as with `stellar contract invoke --send`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub send: Option<SendMode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SendMode {
+    /// Send the transaction only if it writes to the ledger
+    Default,
+    /// Only simulate the transaction
+    No,
+    /// Always send the transaction
+    Yes,
+}
+
+impl std::fmt::Display for SendMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendMode::Default => write!(f, "default"),
+            SendMode::No => write!(f, "no"),
+            SendMode::Yes => write!(f, "yes"),
+        }
+    }
+}
+
+impl AfterDeploy {
+    /// The setting as written, used to detect changes and to find `${...}` references
+    pub fn source(&self) -> String {
+        match self {
+            AfterDeploy::Script(script) => script.clone(),
+            AfterDeploy::Steps(steps) => {
+                serde_json::to_string(steps).expect("after_deploy steps serialize to JSON")
+            }
+        }
+    }
+
+    /// Each step as written: the non-empty lines of a script, or each table as JSON
+    pub fn step_sources(&self) -> Vec<String> {
+        match self {
+            AfterDeploy::Script(script) => script
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(ToString::to_string)
+                .collect(),
+            AfterDeploy::Steps(steps) => steps
+                .iter()
+                .map(|step| {
+                    serde_json::to_string(step).expect("after_deploy steps serialize to JSON")
+                })
+                .collect(),
+        }
+    }
+}
+
+impl AfterDeployStep {
+    /// Arguments for `stellar contract invoke` after `--`, e.g. `["mint", "--to", "..."]`
+    pub fn invoke_args(&self) -> Vec<String> {
+        let mut args = vec![self.function.clone()];
+        for (name, value) in self.arg_values() {
+            args.push(format!("--{name}"));
+            args.push(value);
+        }
+        args
+    }
+
+    /// Argument names with their values as passed on the command line
+    pub fn arg_values(&self) -> impl Iterator<Item = (&str, String)> {
+        self.args.iter().map(|(name, value)| {
+            let value = match value {
+                Value::String(s) => s.clone(),
+                Value::Integer(_) | Value::Float(_) | Value::Boolean(_) | Value::Datetime(_) => {
+                    value.to_string()
+                }
+                Value::Array(_) | Value::Table(_) => {
+                    serde_json::to_string(value).expect("toml values serialize to JSON")
+                }
+            };
+            (name.as_str(), value)
+        })
+    }
+}
+
+impl std::fmt::Display for AfterDeployStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(source) = &self.source {
+            write!(f, "STELLAR_ACCOUNT={source} ")?;
+        }
+        let args = self.invoke_args();
+        let joined =
+            shlex::try_join(args.iter().map(String::as_str)).unwrap_or_else(|_| args.join(" "));
+        write!(f, "{joined}")?;
+        if let Some(send) = self.send {
+            write!(f, " (send: {send})")?;
+        }
+        Ok(())
+    }
+}
+
+fn deserialize_after_deploy<'de, D>(deserializer: D) -> Result<Option<AfterDeploy>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Value::String(script)) => Ok(Some(AfterDeploy::Script(script))),
+        Some(Value::Array(steps)) => steps
+            .into_iter()
+            .map(|step| AfterDeployStep::deserialize(step).map_err(serde::de::Error::custom))
+            .collect::<Result<_, _>>()
+            .map(|steps| Some(AfterDeploy::Steps(steps))),
+        Some(_) => Err(serde::de::Error::custom(
+            "after_deploy must be a string or an array of tables",
+        )),
+    }
 }
 
 impl Default for Contract {
@@ -129,6 +320,7 @@ impl Default for Contract {
             after_deploy: None,
             id: None,
             constructor_args: None,
+            network: None,
         }
     }
 }
@@ -139,6 +331,16 @@ fn default_client() -> bool {
 
 impl Environment {
     pub fn get(workspace_root: &Path, scaffold_env: &str) -> Result<Option<Environment>, Error> {
+        let Some(resolved) = Self::resolve(workspace_root, scaffold_env)? else {
+            return Ok(None);
+        };
+        Ok(Some(Value::Table(resolved).try_into()?))
+    }
+
+    /// Read the settings of `scaffold_env`, deep-merged with the environment it `extends`, or with
+    /// `[default]` if it doesn't extend any, and with defaults filled in for settings that are not
+    /// set. Returns `None` if there is no environments.toml.
+    pub fn resolve(workspace_root: &Path, scaffold_env: &str) -> Result<Option<Table>, Error> {
         let env_toml = workspace_root.join(ENV_FILE);
 
         if !env_toml.exists() {
@@ -146,13 +348,141 @@ impl Environment {
         }
 
         let toml_str = std::fs::read_to_string(env_toml)?;
-        let mut parsed_toml: Environments = toml::from_str(&toml_str)?;
-        let current_env = parsed_toml.remove(scaffold_env);
-        if current_env.is_none() {
+        let environments: Table = toml::from_str(&toml_str)?;
+        if !environments.contains_key(scaffold_env) {
             return Err(Error::NoSettingsForCurrentEnv(scaffold_env.to_string()));
         }
-        Ok(current_env)
+        let mut resolved = resolve_env(&environments, scaffold_env, &mut Vec::new())?;
+        apply_defaults(&mut resolved);
+        Ok(Some(resolved))
+    }
+}
+
+/// Fill in environment settings that are not set. The defaults are the same for every
+/// environment, whatever its name: contracts are deployed from source, `after_deploy` scripts run,
+/// and only `http://` RPC URLs are used over plain HTTP.
+fn apply_defaults(env: &mut Table) {
+    let run_locally = env
+        .get("network")
+        .and_then(|network| network.get("run-locally"))
+        .and_then(Value::as_bool)
+        .unwrap_or_default();
+    env.entry("mode")
+        .or_insert_with(|| Value::String("deploy-from-source".to_string()));
+    env.entry("allow_http").or_insert(Value::Boolean(false));
+    env.entry("run_after_deploy")
+        .or_insert(Value::Boolean(true));
+    env.entry("run_locally")
+        .or_insert(Value::Boolean(run_locally));
+}
+
+fn resolve_env(
+    environments: &Table,
+    name: &str,
+    visiting: &mut Vec<String>,
+) -> Result<Table, Error> {
+    if visiting.iter().any(|visited| visited == name) {
+        visiting.push(name.to_string());
+        return Err(Error::ExtendsCycle(visiting.join(" -> ")));
+    }
+    let mut env = match environments.get(name) {
+        Some(Value::Table(env)) => env.clone(),
+        _ => return Err(Error::NotATable(name.to_string())),
+    };
+
+    let parent = match env.remove("extends") {
+        Some(Value::String(parent)) => parent,
+        Some(_) => return Err(Error::InvalidExtends(name.to_string())),
+        None if name != DEFAULT_ENV && environments.contains_key(DEFAULT_ENV) => {
+            DEFAULT_ENV.to_string()
+        }
+        None => return Ok(env),
+    };
+    if !environments.contains_key(&parent) {
+        return Err(Error::UnknownParent(name.to_string(), parent));
+    }
+
+    visiting.push(name.to_string());
+    let mut resolved = resolve_env(environments, &parent, visiting)?;
+    visiting.pop();
+    merge_env(&mut resolved, env);
+    Ok(resolved)
+}
+
+/// Deep-merge an environment into the one it inherits from. `accounts` are merged by name, and
+/// a network set by name or by RPC url, for the environment or for one of its contracts, replaces
+/// an inherited one instead of mixing with it.
+fn merge_env(base: &mut Table, mut env: Table) {
+    if let (Some(Value::Array(base_accounts)), Some(Value::Array(accounts))) =
+        (base.get("accounts"), env.get_mut("accounts"))
+    {
+        *accounts = merge_accounts(base_accounts, accounts);
+    }
+    reset_network_identity(base, &env);
+    if let (Some(Value::Table(base_contracts)), Some(Value::Table(contracts))) =
+        (base.get_mut("contracts"), env.get("contracts"))
+    {
+        for (name, contract) in contracts {
+            if let (Some(Value::Table(base_contract)), Value::Table(contract)) =
+                (base_contracts.get_mut(name), contract)
+            {
+                reset_network_identity(base_contract, contract);
+            }
+        }
+    }
+    merge(base, env);
+}
+
+/// Drop the inherited `network` identity from `base` if `overlay` sets its own
+fn reset_network_identity(base: &mut Table, overlay: &Table) {
+    if let (Some(Value::Table(base_network)), Some(Value::Table(network))) =
+        (base.get_mut("network"), overlay.get("network"))
+    {
+        if NETWORK_IDENTITY
+            .iter()
+            .any(|key| network.contains_key(*key))
+        {
+            for key in NETWORK_IDENTITY {
+                base_network.remove(key);
+            }
+        }
+    }
+}
+
+/// Merge `overlay` into `base`: tables are merged recursively and any other value in `overlay`
+/// replaces the one in `base`
+fn merge(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        if let Some(existing) = base.get_mut(&key) {
+            match (existing, value) {
+                (Value::Table(existing), Value::Table(overlay)) => merge(existing, overlay),
+                (existing, value) => *existing = value,
+            }
+        } else {
+            base.insert(key, value);
+        }
+    }
+}
+
+fn merge_accounts(base: &[Value], overlay: &[Value]) -> Vec<Value> {
+    fn name(account: &Value) -> Option<&str> {
+        match account {
+            Value::String(name) => Some(name),
+            Value::Table(account) => account.get("name").and_then(Value::as_str),
+            _ => None,
+        }
+    }
+    let mut merged = base.to_vec();
+    for account in overlay {
+        match merged
+            .iter_mut()
+            .find(|existing| name(existing).is_some() && name(existing) == name(account))
+        {
+            Some(existing) => existing.clone_from(account),
+            None => merged.push(account.clone()),
+        }
     }
+    merged
 }
 
 impl From<&Network> for stellar_cli::config::network::Args {
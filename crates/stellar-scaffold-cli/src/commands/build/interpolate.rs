@@ -0,0 +1,211 @@
+//! `${...}` interpolation for `constructor_args` and `after_deploy` in environments.toml
+//!
+//! - `${VAR}` is replaced with the environment variable `VAR`
+//! - `${VAR:-default}` falls back to `default` if `VAR` is unset or empty
+//! - `${account:<name>}` is replaced with the public key of the key named `<name>`
+//! - `${contract:<name>}` is replaced with the ID of a contract deployed earlier in the same run
+//! - `$${` is a literal `${`
+use std::collections::HashMap;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(
+        "⛔ ️environment variable {0:?} is not set; set it or give a default with ${{{0}:-default}}"
+    )]
+    UndefinedVariable(String),
+    #[error("⛔ ️${{account:{0}}}: no key named {0:?}; add it to the environment's accounts or create it with `stellar keys generate {0}`")]
+    UnknownAccount(String),
+    #[error("⛔ ️${{contract:{0}}}: contract {0:?} has not been deployed earlier in this run")]
+    UnknownContract(String),
+    #[error("⛔ ️unterminated ${{ in {0:?}")]
+    Unterminated(String),
+    #[error("⛔ ️invalid reference ${{{0}}}; expected ${{VAR}}, ${{VAR:-default}}, ${{account:<name>}} or ${{contract:<name>}}")]
+    InvalidReference(String),
+}
+
+/// A `${...}` reference that is resolved by the build rather than from the environment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reference<'a> {
+    Account(&'a str),
+    Contract(&'a str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment<'a> {
+    Text(&'a str),
+    Var {
+        name: &'a str,
+        default: Option<&'a str>,
+    },
+    Reference(Reference<'a>),
+}
+
+/// Values for the `${account:...}` and `${contract:...}` references of a string
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    /// Key names mapped to their public keys
+    pub accounts: HashMap<String, String>,
+    /// Contract names mapped to their contract IDs
+    pub contracts: HashMap<String, String>,
+}
+
+impl Context {
+    /// Replace every `${...}` reference in `input`
+    pub fn resolve(&self, input: &str) -> Result<String, Error> {
+        let mut resolved = String::with_capacity(input.len());
+        for segment in parse(input)? {
+            match segment {
+                Segment::Text(text) => resolved.push_str(text),
+                Segment::Var { name, default } => {
+                    match (std::env::var(name).ok().filter(|v| !v.is_empty()), default) {
+                        (Some(value), _) => resolved.push_str(&value),
+                        (None, Some(default)) => resolved.push_str(default),
+                        (None, None) => return Err(Error::UndefinedVariable(name.to_string())),
+                    }
+                }
+                Segment::Reference(Reference::Account(name)) => resolved.push_str(
+                    self.accounts
+                        .get(name)
+                        .ok_or_else(|| Error::UnknownAccount(name.to_string()))?,
+                ),
+                Segment::Reference(Reference::Contract(name)) => resolved.push_str(
+                    self.contracts
+                        .get(name)
+                        .ok_or_else(|| Error::UnknownContract(name.to_string()))?,
+                ),
+            }
+        }
+        Ok(resolved)
+    }
+}
+
+/// The `${account:...}` and `${contract:...}` references in `input`
+pub fn references(input: &str) -> Result<Vec<Reference<'_>>, Error> {
+    Ok(parse(input)?
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Reference(reference) => Some(reference),
+            _ => None,
+        })
+        .collect())
+}
+
+fn parse(input: &str) -> Result<Vec<Segment<'_>>, Error> {
+    let mut segments = Vec::new();
+    let mut rest = input;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            // `$${` is an escaped `${`
+            segments.push(Segment::Text(&rest[..start]));
+            segments.push(Segment::Text("{"));
+            rest = &rest[start + 2..];
+            continue;
+        }
+        segments.push(Segment::Text(&rest[..start]));
+        let Some(end) = rest[start..].find('}') else {
+            return Err(Error::Unterminated(input.to_string()));
+        };
+        segments.push(parse_reference(&rest[start + 2..start + end])?);
+        rest = &rest[start + end + 1..];
+    }
+    segments.push(Segment::Text(rest));
+    Ok(segments)
+}
+
+fn parse_reference(expr: &str) -> Result<Segment<'_>, Error> {
+    let invalid = || Error::InvalidReference(expr.to_string());
+    if let Some(name) = expr.strip_prefix("account:") {
+        return valid_name(name)
+            .then_some(Segment::Reference(Reference::Account(name)))
+            .ok_or_else(invalid);
+    }
+    if let Some(name) = expr.strip_prefix("contract:") {
+        return valid_name(name)
+            .then_some(Segment::Reference(Reference::Contract(name)))
+            .ok_or_else(invalid);
+    }
+    let (name, default) = match expr.split_once(":-") {
+        Some((name, default)) => (name, Some(default)),
+        None => (expr, None),
+    };
+    let is_var_name = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !is_var_name {
+        return Err(invalid());
+    }
+    Ok(Segment::Var { name, default })
+}
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> Context {
+        Context {
+            accounts: HashMap::from([("alice".to_string(), "GALICE".to_string())]),
+            contracts: HashMap::from([("token".to_string(), "CTOKEN".to_string())]),
+        }
+    }
+
+    #[test]
+    fn resolves_references() {
+        std::env::set_var("INTERPOLATE_TEST_SET", "set");
+        let resolved = context()
+            .resolve("--a ${INTERPOLATE_TEST_SET} --b ${account:alice} --c ${contract:token}")
+            .unwrap();
+        assert_eq!(resolved, "--a set --b GALICE --c CTOKEN");
+    }
+
+    #[test]
+    fn uses_default_for_unset_variables() {
+        let resolved = context()
+            .resolve("--name ${INTERPOLATE_TEST_UNSET:-Token A}")
+            .unwrap();
+        assert_eq!(resolved, "--name Token A");
+    }
+
+    #[test]
+    fn errors_on_undefined_references() {
+        assert!(matches!(
+            context().resolve("${INTERPOLATE_TEST_UNSET}"),
+            Err(Error::UndefinedVariable(name)) if name == "INTERPOLATE_TEST_UNSET"
+        ));
+        assert!(matches!(
+            context().resolve("${account:bob}"),
+            Err(Error::UnknownAccount(name)) if name == "bob"
+        ));
+        assert!(matches!(
+            context().resolve("${contract:nft}"),
+            Err(Error::UnknownContract(name)) if name == "nft"
+        ));
+        assert!(matches!(
+            context().resolve("--a ${account:alice"),
+            Err(Error::Unterminated(_))
+        ));
+        assert!(matches!(
+            context().resolve("${not a var}"),
+            Err(Error::InvalidReference(_))
+        ));
+    }
+
+    #[test]
+    fn leaves_escapes_and_shell_syntax_alone() {
+        let resolved = context().resolve("$${HOME} $(echo hi) $5").unwrap();
+        assert_eq!(resolved, "${HOME} $(echo hi) $5");
+    }
+
+    #[test]
+    fn lists_references() {
+        assert_eq!(
+            references("${contract:token} ${HOME} ${account:alice} $${contract:nft}").unwrap(),
+            vec![Reference::Contract("token"), Reference::Account("alice")]
+        );
+    }
+}
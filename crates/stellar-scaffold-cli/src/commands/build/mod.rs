@@ -2,19 +2,21 @@
 use crate::commands::build::Error::EmptyPackageName;
 use crate::commands::version;
 use cargo_metadata::camino::Utf8PathBuf;
-use cargo_metadata::{Metadata, MetadataCommand, Package};
+use cargo_metadata::{Metadata, MetadataCommand, Package, PackageId};
 use clap::Parser;
 use clients::ScaffoldEnv;
 use serde_json::Value;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::{fmt::Debug, io, path::Path, process::ExitStatus};
 use stellar_cli::commands::contract::build::Cmd;
 use stellar_cli::commands::{contract::build, global};
 use stellar_cli::print::Print;
 
 pub mod clients;
+pub mod deployments;
 pub mod docker;
 pub mod env_toml;
+pub mod interpolate;
 
 /// Build a contract from source
 ///
@@ -75,17 +77,33 @@ impl Command {
         Ok(stellar_build::deps::get_workspace(&packages)?)
     }
 
+    /// Workspace contracts in build order, along with the names of the workspace contracts each
+    /// one depends on
+    pub fn sorted_packages(
+        &self,
+        metadata: &Metadata,
+    ) -> Result<(Vec<Package>, HashMap<String, Vec<String>>), Error> {
+        let packages = self.packages(metadata)?;
+        let dependencies = stellar_build::deps::contract_dependencies(&packages)?;
+        let packages = stellar_build::deps::sort_workspace(&packages, &dependencies);
+        let names = Self::contract_dependency_names(&packages, &dependencies);
+        Ok((packages, names))
+    }
+
     async fn start_local_docker_if_needed(
         &self,
         workspace_root: &Path,
         env: &ScaffoldEnv,
+        printer: &Print,
     ) -> Result<(), Error> {
         if let Some(current_env) = env_toml::Environment::get(workspace_root, &env.to_string())? {
-            if current_env.network.run_locally {
+            if current_env.run_locally {
+                printer.infoln("Starting local Stellar Docker container...");
                 docker::start_local_stellar().await.map_err(|e| {
                     eprintln!("Failed to start Stellar Docker container: {e:?}");
                     Error::DockerStart
                 })?;
+                printer.checkln("Local Stellar network is healthy and running.");
             }
         }
         Ok(())
@@ -94,16 +112,12 @@ impl Command {
     pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
         let printer = Print::new(global_args.quiet);
         let metadata = self.metadata()?;
-        let packages = self.list_packages(&metadata)?;
+        let (packages, contract_dependencies) = self.sorted_packages(&metadata)?;
         let workspace_root = metadata.workspace_root.as_std_path();
 
         if let Some(env) = &self.build_clients_args.env {
-            if env == &ScaffoldEnv::Development {
-                printer.infoln("Starting local Stellar Docker container...");
-                self.start_local_docker_if_needed(workspace_root, env)
-                    .await?;
-                printer.checkln("Local Stellar network is healthy and running.");
-            }
+            self.start_local_docker_if_needed(workspace_root, env, &printer)
+                .await?;
         }
 
         if self.list {
@@ -125,6 +139,7 @@ impl Command {
             build_clients_args.workspace_root = Some(metadata.workspace_root.into_std_path_buf());
             build_clients_args.out_dir.clone_from(&self.build.out_dir);
             build_clients_args.global_args = Some(global_args.clone());
+            build_clients_args.contract_dependencies = contract_dependencies;
             build_clients_args
                 .run(packages.iter().map(|p| p.name.replace('-', "_")).collect())
                 .await?;
@@ -133,6 +148,26 @@ impl Command {
         Ok(())
     }
 
+    /// Map each contract name to the names of the contracts in the workspace it depends on
+    fn contract_dependency_names(
+        packages: &[Package],
+        dependencies: &HashMap<PackageId, Vec<PackageId>>,
+    ) -> HashMap<String, Vec<String>> {
+        packages
+            .iter()
+            .map(|p| {
+                let deps = dependencies
+                    .get(&p.id)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|id| packages.iter().find(|dep| &dep.id == id))
+                    .map(|dep| dep.name.replace('-', "_"))
+                    .collect();
+                (p.name.replace('-', "_"), deps)
+            })
+            .collect()
+    }
+
     fn packages(&self, metadata: &Metadata) -> Result<Vec<Package>, Error> {
         if let Some(package) = &self.build.package {
             let package = metadata
@@ -176,7 +211,7 @@ impl Command {
         cmd.exec()
     }
 
-    fn create_cmd(&self, p: &Package, target_dir: &Utf8PathBuf) -> Result<Cmd, Error> {
+    pub(crate) fn create_cmd(&self, p: &Package, target_dir: &Utf8PathBuf) -> Result<Cmd, Error> {
         let mut cmd = self.build.clone();
         cmd.out_dir = cmd.out_dir.or_else(|| {
             Some(stellar_build::deps::stellar_wasm_out_dir(
@@ -215,6 +250,12 @@ impl Command {
                             meta_map
                                 .insert("repository".to_string(), p.repository.clone().unwrap());
                         }
+                        if let Some(license) = &p.license {
+                            meta_map.insert("license".to_string(), license.clone());
+                        }
+                        if let Some(description) = &p.description {
+                            meta_map.insert("description".to_string(), description.clone());
+                        }
                     }
 
                     Self::rec_add_meta(String::new(), &mut meta_map, val);
@@ -0,0 +1,21 @@
+use clap::Parser;
+
+pub mod show;
+
+#[derive(Parser, Debug)]
+pub struct Cmd {
+    #[command(subcommand)]
+    pub cmd: Command,
+}
+
+#[derive(Parser, Debug)]
+pub enum Command {
+    /// Print the settings of an environment from environments.toml, with `extends` and `[default]` applied
+    Show(show::Cmd),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Show(#[from] show::Error),
+}
@@ -0,0 +1,48 @@
+use cargo_metadata::MetadataCommand;
+use clap::Parser;
+use std::path::PathBuf;
+use toml::value::Table;
+use toml::Value;
+
+use crate::commands::build::{clients::ScaffoldEnv, env_toml};
+
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Name of the environment to show, e.g. `development`
+    #[arg(env = "STELLAR_SCAFFOLD_ENV")]
+    pub env: ScaffoldEnv,
+    /// Path to Cargo.toml of the workspace
+    #[arg(long)]
+    pub manifest_path: Option<PathBuf>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Metadata(#[from] cargo_metadata::Error),
+    #[error(transparent)]
+    EnvironmentsToml(#[from] env_toml::Error),
+    #[error("⛔ ️no environments.toml found in {0:?}")]
+    NoEnvironmentsToml(PathBuf),
+    #[error(transparent)]
+    Serializing(#[from] toml::ser::Error),
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let mut metadata = MetadataCommand::new();
+        metadata.no_deps();
+        if let Some(manifest_path) = &self.manifest_path {
+            metadata.manifest_path(manifest_path);
+        }
+        let workspace_root = metadata.exec()?.workspace_root.into_std_path_buf();
+
+        let env = self.env.to_string();
+        let resolved = env_toml::Environment::resolve(&workspace_root, &env)?
+            .ok_or(Error::NoEnvironmentsToml(workspace_root))?;
+        let mut environments = Table::new();
+        environments.insert(env, Value::Table(resolved));
+        print!("{}", toml::to_string(&environments)?);
+        Ok(())
+    }
+}
@@ -4,8 +4,10 @@ use clap::{command, CommandFactory, FromArgMatches, Parser};
 use stellar_cli;
 
 pub mod build;
+pub mod env;
 pub mod generate;
 pub mod init;
+pub mod plan;
 pub mod update_env;
 pub mod upgrade;
 pub mod version;
@@ -51,6 +53,10 @@ impl Root {
             Cmd::Upgrade(upgrade_info) => upgrade_info.run(&self.global_args).await?,
             Cmd::UpdateEnv(e) => e.run()?,
             Cmd::Watch(watch_info) => watch_info.run(&self.global_args).await?,
+            Cmd::Plan(plan) => plan.run(&self.global_args).await?,
+            Cmd::Env(env) => match &env.cmd {
+                env::Command::Show(show) => show.run()?,
+            },
         }
         Ok(())
     }
@@ -85,6 +91,12 @@ pub enum Cmd {
 
     /// Monitor contracts and environments.toml for changes and rebuild as needed
     Watch(watch::Cmd),
+
+    /// Show which contracts `build --build-clients` would upload, deploy or upgrade for an environment, and which `after_deploy` scripts would run, without sending any transactions
+    Plan(plan::Cmd),
+
+    /// Inspect environments defined in environments.toml
+    Env(env::Cmd),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -102,4 +114,8 @@ pub enum Error {
     UpdateEnv(#[from] update_env::Error),
     #[error(transparent)]
     Watch(#[from] watch::Error),
+    #[error(transparent)]
+    Plan(#[from] plan::Error),
+    #[error(transparent)]
+    EnvShow(#[from] env::show::Error),
 }
@@ -0,0 +1,165 @@
+use clap::Parser;
+use serde::Serialize;
+use stellar_cli::commands::global;
+
+use crate::commands::build::{self, clients};
+
+/// Show what `build --build-clients` would do for an environment without sending any transactions
+#[derive(Parser, Debug, Clone)]
+#[group(skip)]
+pub struct Cmd {
+    #[command(flatten)]
+    pub build_cmd: build::Command,
+    /// Format of the plan
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable summary
+    Text,
+    /// JSON, e.g. for gating CI on the planned changes
+    Json,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Build(#[from] build::Error),
+    #[error(transparent)]
+    Clients(#[from] clients::Error),
+    #[error(transparent)]
+    Manifest(#[from] cargo_metadata::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("⛔ ️no environments.toml found in {0:?}")]
+    NoEnvironmentsToml(std::path::PathBuf),
+}
+
+/// What `build --build-clients` would do with a contract
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    /// Client generation is disabled with `client = false`
+    Skip,
+    /// The contract ID is fixed in environments.toml; only the client is generated
+    Pinned,
+    /// The deployed contract already runs the local wasm
+    UpToDate,
+    /// A new contract instance would be deployed
+    Deploy,
+    /// The deployed contract would be upgraded in place through its `upgrade` function
+    Upgrade,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ContractPlan {
+    pub name: String,
+    pub action: Action,
+    /// Whether the local wasm still needs to be uploaded to the network
+    pub upload: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wasm_hash: Option<String>,
+    /// Contract ID currently in use, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_id: Option<String>,
+    /// Network the contract is deployed to, if it overrides the environment's network
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_passphrase: Option<String>,
+    /// `after_deploy` commands that would run
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub after_deploy: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Plan {
+    pub env: String,
+    pub network_passphrase: String,
+    pub contracts: Vec<ContractPlan>,
+}
+
+impl Cmd {
+    pub async fn run(&self, global_args: &global::Args) -> Result<(), Error> {
+        let metadata = self.build_cmd.metadata()?;
+        // Same order and dependencies as `build --build-clients`
+        let (packages, contract_dependencies) = self.build_cmd.sorted_packages(&metadata)?;
+        let target_dir = &metadata.target_directory;
+
+        // Building only compiles the contracts locally, so the plan reflects the current sources
+        let mut quiet_global_args = global_args.clone();
+        quiet_global_args.quiet = true;
+        for p in &packages {
+            self.build_cmd
+                .create_cmd(p, target_dir)?
+                .run(&quiet_global_args)
+                .map_err(build::Error::from)?;
+        }
+
+        let mut args = self.build_cmd.build_clients_args.clone();
+        args.workspace_root = Some(metadata.workspace_root.clone().into_std_path_buf());
+        args.out_dir.clone_from(&self.build_cmd.build.out_dir);
+        args.global_args = Some(quiet_global_args);
+        args.contract_dependencies = contract_dependencies;
+
+        let plan = args
+            .plan(packages.iter().map(|p| p.name.replace('-', "_")).collect())
+            .await?
+            .ok_or_else(|| {
+                Error::NoEnvironmentsToml(metadata.workspace_root.clone().into_std_path_buf())
+            })?;
+
+        match self.output {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&plan)?),
+            OutputFormat::Text => print_plan(&plan),
+        }
+        Ok(())
+    }
+}
+
+fn print_plan(plan: &Plan) {
+    println!(
+        "Plan for {:?} on network {:?}:",
+        plan.env, plan.network_passphrase
+    );
+    for contract in &plan.contracts {
+        let name = match &contract.network_passphrase {
+            Some(network_passphrase) => format!("{} (on {network_passphrase:?})", contract.name),
+            None => contract.name.clone(),
+        };
+        let contract_id = contract.contract_id.as_deref().unwrap_or_default();
+        let upload = if contract.upload {
+            format!(
+                "upload wasm {}, ",
+                contract.wasm_hash.as_deref().unwrap_or_default()
+            )
+        } else {
+            String::new()
+        };
+        match contract.action {
+            Action::Skip => println!("  - {name}: skipped (client = false)"),
+            Action::Pinned => println!("  @ {name}: pinned to {contract_id}"),
+            Action::UpToDate => println!("  = {name}: up to date ({contract_id})"),
+            Action::Deploy if contract_id.is_empty() => println!("  + {name}: {upload}deploy"),
+            Action::Deploy => println!("  + {name}: {upload}redeploy (replacing {contract_id})"),
+            Action::Upgrade => println!("  ~ {name}: {upload}upgrade {contract_id}"),
+        }
+        for line in &contract.after_deploy {
+            println!("      after_deploy: {line}");
+        }
+    }
+
+    let count = |action| {
+        plan.contracts
+            .iter()
+            .filter(|contract| contract.action == action)
+            .count()
+    };
+    println!(
+        "{} to upload, {} to deploy, {} to upgrade, {} up to date",
+        plan.contracts.iter().filter(|c| c.upload).count(),
+        count(Action::Deploy),
+        count(Action::Upgrade),
+        count(Action::UpToDate),
+    );
+}
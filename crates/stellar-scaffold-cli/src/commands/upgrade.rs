@@ -1,8 +1,9 @@
 use crate::arg_parsing::ArgParser;
-use crate::commands::build::env_toml::{Account, Contract, Environment, Network};
+use crate::commands::build::env_toml::{Account, Contract, Environment, Mode, Network};
 use clap::Parser;
 use degit::degit;
 use indexmap::IndexMap;
+use std::collections::HashMap;
 use std::fs;
 use std::fs::{create_dir_all, metadata, read_dir, write};
 use std::io;
@@ -230,9 +231,13 @@ impl Cmd {
                 rpc_url: Some("http://localhost:8000/rpc".to_string()),
                 network_passphrase: Some("Standalone Network ; February 2017".to_string()),
                 rpc_headers: None,
-                run_locally: true,
+                run_locally: false,
             },
             contracts: (!contract_configs.is_empty()).then_some(contract_configs),
+            mode: Mode::DeployFromSource,
+            allow_http: true,
+            run_after_deploy: true,
+            run_locally: true,
         };
 
         let mut doc = DocumentMut::new();
@@ -250,8 +255,8 @@ impl Cmd {
         network_table["rpc-url"] = value(env_config.network.rpc_url.as_ref().unwrap());
         network_table["network-passphrase"] =
             value(env_config.network.network_passphrase.as_ref().unwrap());
-        network_table["run-locally"] = value(env_config.network.run_locally);
         dev_table["network"] = Item::Table(network_table);
+        dev_table["run_locally"] = value(env_config.run_locally);
 
         // Add contracts
         let contracts_table = env_config
@@ -339,7 +344,11 @@ impl Cmd {
         // Run scaffold build to generate WASM files
         let build_cmd = build::Command {
             build_clients_args: build::clients::Args {
-                env: Some(build::clients::ScaffoldEnv::Development),
+                env: Some(build::clients::ScaffoldEnv::development()),
+                refresh_deployments: false,
+                jobs: 1,
+                rerun_after_deploy: Vec::new(),
+                contract_dependencies: HashMap::new(),
                 workspace_root: Some(self.workspace_path.clone()),
                 out_dir: None,
                 global_args: Some(global_args.clone()),
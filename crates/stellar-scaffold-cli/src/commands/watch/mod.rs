@@ -138,7 +138,7 @@ impl Cmd {
         let rebuild_state = Arc::new(Mutex::new(false));
         let metadata = &self.build_cmd.metadata()?;
         let env_toml_dir = metadata.workspace_root.as_std_path();
-        if env_toml::Environment::get(env_toml_dir, &ScaffoldEnv::Development.to_string())?
+        if env_toml::Environment::get(env_toml_dir, &ScaffoldEnv::development().to_string())?
             .is_none()
         {
             return Ok(());
@@ -231,7 +231,7 @@ impl Cmd {
         self.build_cmd
             .build_clients_args
             .env
-            .get_or_insert(ScaffoldEnv::Development);
+            .get_or_insert_with(ScaffoldEnv::development);
         Arc::new((self.build_cmd.clone(), global_args.clone()))
     }
 }
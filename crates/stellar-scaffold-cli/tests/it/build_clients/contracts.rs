@@ -232,6 +232,111 @@ soroban_token_contract.client = false
     });
 }
 
+#[test]
+fn deployments_lock_records_contracts() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+[development.network]
+rpc-url = "http://localhost:8000/rpc"
+network-passphrase = "Standalone Network ; February 2017"
+
+[development.contracts]
+soroban_hello_world_contract.client = true
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+soroban_token_contract.client = false
+"#,
+        );
+
+        env.stellar_scaffold_env("development", false)
+            .assert()
+            .success();
+
+        let lock_path = env.cwd.join(".stellar/deployments.lock");
+        let lock = std::fs::read_to_string(&lock_path).expect("deployments.lock was not written");
+        assert!(lock.contains(
+            "[development.\"Standalone Network ; February 2017\".soroban_hello_world_contract]"
+        ));
+        assert!(lock.contains("contract_id = \"C"));
+
+        // A second build is answered from the lockfile, without uploading the wasm again
+        let stderr = env
+            .stellar_scaffold_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains("Contract \"soroban_hello_world_contract\" is up to date"));
+        assert!(
+            !stderr.contains("Installing \"soroban_hello_world_contract\" wasm bytecode on-chain")
+        );
+        assert_eq!(std::fs::read_to_string(&lock_path).unwrap(), lock);
+    });
+}
+
+#[test]
+fn plan_reports_pending_deployments() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+[development.network]
+rpc-url = "http://localhost:8000/rpc"
+network-passphrase = "Standalone Network ; February 2017"
+
+[development.contracts]
+soroban_hello_world_contract.client = true
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+soroban_token_contract.client = false
+"#,
+        );
+
+        let plan = |env: &TestEnv| -> serde_json::Value {
+            let output = env
+                .scaffold("plan")
+                .args(["development", "--output", "json"])
+                .assert()
+                .success()
+                .get_output()
+                .stdout
+                .clone();
+            serde_json::from_slice(&output).expect("plan output is not valid JSON")
+        };
+        let action = |plan: &serde_json::Value, name: &str| {
+            plan["contracts"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|contract| contract["name"] == name)
+                .map(|contract| contract["action"].as_str().unwrap().to_string())
+                .unwrap()
+        };
+
+        let before = plan(env);
+        assert_eq!(action(&before, "soroban_hello_world_contract"), "deploy");
+        assert_eq!(action(&before, "soroban_increment_contract"), "skip");
+        // Planning must not deploy anything
+        assert!(!env.cwd.join(".stellar/deployments.lock").exists());
+
+        env.stellar_scaffold_env("development", false)
+            .assert()
+            .success();
+
+        let after = plan(env);
+        assert_eq!(action(&after, "soroban_hello_world_contract"), "up-to-date");
+    });
+}
+
 fn extract_contract_id(stderr: &str) -> Option<String> {
     stderr
         .lines()
@@ -493,3 +598,194 @@ STELLAR_ACCOUNT=bob --symbol ABND --decimal 7 --name abundance --admin bb
             .exists());
     });
 }
+
+#[test]
+fn contract_references_order_deployments() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+[development.network]
+rpc-url = "http://localhost:8000/rpc"
+network-passphrase = "Standalone Network ; February 2017"
+
+[development.contracts]
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+
+[development.contracts.soroban_token_contract]
+constructor_args = "--symbol ABND --decimal 7 --name abundance --admin ${contract:soroban_hello_world_contract}"
+
+[development.contracts.soroban_hello_world_contract]
+client = true
+"#,
+        );
+
+        let stderr = env
+            .stellar_scaffold_env("development", true)
+            .assert()
+            .success()
+            .stderr_as_str();
+        let hello = stderr
+            .find("Instantiating \"soroban_hello_world_contract\"")
+            .expect("hello world contract was not deployed");
+        let token = stderr
+            .find("Instantiating \"soroban_token_contract\"")
+            .expect("token contract was not deployed");
+        assert!(hello < token);
+    });
+}
+
+#[test]
+fn contract_reference_cycle_is_an_error() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+[development.network]
+rpc-url = "http://localhost:8000/rpc"
+network-passphrase = "Standalone Network ; February 2017"
+
+[development.contracts]
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+soroban_token_contract.client = false
+
+[development.contracts.soroban_hello_world_contract]
+after_deploy = "hello --to ${contract:soroban_increment_contract}"
+
+[development.contracts.soroban_increment_contract]
+after_deploy = "increment --by ${contract:soroban_hello_world_contract}"
+"#,
+        );
+
+        let stderr = env
+            .scaffold("plan")
+            .arg("development")
+            .assert()
+            .failure()
+            .stderr_as_str();
+        assert!(stderr.contains(
+            "contracts reference each other in a cycle: soroban_hello_world_contract -> soroban_increment_contract -> soroban_hello_world_contract"
+        ));
+    });
+}
+
+#[test]
+fn parallel_builds_keep_dependency_order_and_output_order() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+[development.network]
+rpc-url = "http://localhost:8000/rpc"
+network-passphrase = "Standalone Network ; February 2017"
+
+[development.contracts]
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+
+[development.contracts.soroban_token_contract]
+constructor_args = "--symbol ABND --decimal 7 --name abundance --admin ${contract:soroban_hello_world_contract}"
+
+[development.contracts.soroban_hello_world_contract]
+client = true
+
+[development.contracts.soroban_increment_contract]
+client = true
+"#,
+        );
+
+        let stderr = env
+            .stellar_scaffold_env("development", true)
+            .args(["--jobs", "4"])
+            .assert()
+            .success()
+            .stderr_as_str();
+        let hello = stderr
+            .find("Instantiating \"soroban_hello_world_contract\"")
+            .expect("hello world contract was not deployed");
+        let token = stderr
+            .find("Instantiating \"soroban_token_contract\"")
+            .expect("token contract was not deployed");
+        assert!(hello < token);
+        assert!(stderr.contains("Successfully processed: 3"));
+
+        // The lockfile lists contracts in the same order as the plan, whichever finished first
+        let lock = std::fs::read_to_string(env.cwd.join(".stellar/deployments.lock")).unwrap();
+        let output = env
+            .scaffold("plan")
+            .args(["development", "--output", "json"])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let plan: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        let positions = plan["contracts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|contract| contract["action"] != "skip")
+            .map(|contract| {
+                let name = contract["name"].as_str().unwrap();
+                lock.find(&format!(".{name}]"))
+                    .unwrap_or_else(|| panic!("{name} missing from deployments.lock"))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(positions.len(), 3);
+        assert!(positions.is_sorted());
+    });
+}
+
+#[test]
+fn contracts_depending_on_a_failed_contract_are_skipped() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+[development.network]
+rpc-url = "http://localhost:8000/rpc"
+network-passphrase = "Standalone Network ; February 2017"
+
+[development.contracts]
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+
+[development.contracts.soroban_token_contract]
+constructor_args = "--symbol ABND --decimal 7 --name abundance --admin bb"
+
+[development.contracts.soroban_hello_world_contract]
+after_deploy = "hello --to ${contract:soroban_token_contract}"
+"#,
+        );
+
+        let stderr = env
+            .stellar_scaffold_env("development", true)
+            .args(["--jobs", "4"])
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains("Failed: 2"));
+        assert!(stderr.contains(
+            "soroban_hello_world_contract: skipped because \"soroban_token_contract\" failed"
+        ));
+        assert!(
+            !stderr.contains("Installing \"soroban_hello_world_contract\" wasm bytecode on-chain")
+        );
+    });
+}
@@ -1,4 +1,4 @@
-use stellar_scaffold_test::{find_binary, TestEnv};
+use stellar_scaffold_test::{find_binary, AssertExt, TestEnv};
 
 #[test]
 fn build_command_runs_init() {
@@ -255,3 +255,213 @@ test_init --resolution 300000 --assets '[{{"Stellar": "$({binary_path_str} contr
         );
     });
 }
+
+#[test]
+fn init_script_interpolates_variables() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+{ name = "alice" },
+]
+
+[development.network]
+rpc-url = "http://localhost:8000/rpc"
+network-passphrase = "Standalone Network ; February 2017"
+
+[development.contracts]
+soroban_hello_world_contract.client = false
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+
+[development.contracts.soroban_token_contract]
+client = true
+constructor_args = """
+--symbol ABND --decimal 7 --name "${TOKEN_NAME:-abundance token}" --admin ${account:alice}
+"""
+after_deploy = """
+mint --amount 2000000 --to ${account:alice}
+"""
+"#,
+        );
+
+        let output = env
+            .stellar_scaffold_env("development", true)
+            .output()
+            .expect("Failed to execute command");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        println!("stderr: {stderr}");
+        assert!(output.status.success());
+        assert!(stderr.contains(" -- mint --amount 2000000 --to G"));
+
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+{ name = "alice" },
+]
+
+[development.network]
+rpc-url = "http://localhost:8000/rpc"
+network-passphrase = "Standalone Network ; February 2017"
+
+[development.contracts]
+soroban_hello_world_contract.client = false
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+
+[development.contracts.soroban_token_contract]
+client = true
+constructor_args = """
+--symbol ABND --decimal 7 --name ${UNSET_TOKEN_NAME} --admin alice
+"""
+"#,
+        );
+
+        let output = env
+            .stellar_scaffold_env("development", true)
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(String::from_utf8_lossy(&output.stderr)
+            .contains("environment variable \"UNSET_TOKEN_NAME\" is not set"));
+    });
+}
+
+#[test]
+fn after_deploy_steps_run_and_are_validated() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        let environments_toml = |function: &str| {
+            format!(
+                r#"
+development.accounts = [
+{{ name = "alice" }},
+]
+
+[development.network]
+rpc-url = "http://localhost:8000/rpc"
+network-passphrase = "Standalone Network ; February 2017"
+
+[development.contracts]
+soroban_hello_world_contract.client = false
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+
+[development.contracts.soroban_token_contract]
+client = true
+constructor_args = """
+STELLAR_ACCOUNT=alice --symbol ABND --decimal 7 --name abundance --admin alice
+"""
+
+[[development.contracts.soroban_token_contract.after_deploy]]
+fn = "{function}"
+args = {{ to = "alice", amount = 2000000 }}
+source = "alice"
+"#
+            )
+        };
+
+        env.set_environments_toml(environments_toml("mitn"));
+        let stderr = env
+            .scaffold("plan")
+            .arg("development")
+            .assert()
+            .failure()
+            .stderr_as_str();
+        assert!(stderr.contains(
+            "invalid after_deploy step for \"soroban_token_contract\": the contract has no function \"mitn\""
+        ));
+
+        env.set_environments_toml(environments_toml("mint"));
+        let output = env
+            .stellar_scaffold_env("development", true)
+            .output()
+            .expect("Failed to execute command");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        println!("stderr: {stderr}");
+        assert!(output.status.success());
+        assert!(stderr.contains("--source-account alice -- mint --to alice --amount 2000000"));
+        assert!(stderr.contains(
+            "✅ After deploy script for \"soroban_token_contract\" completed successfully"
+        ));
+    });
+}
+
+#[test]
+fn after_deploy_resumes_from_failed_step() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        let environments_toml = |after_deploy: &str| {
+            format!(
+                r#"
+development.accounts = [
+{{ name = "alice" }},
+]
+
+[development.network]
+rpc-url = "http://localhost:8000/rpc"
+network-passphrase = "Standalone Network ; February 2017"
+
+[development.contracts]
+soroban_hello_world_contract.client = false
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+
+[development.contracts.soroban_token_contract]
+client = true
+constructor_args = """
+STELLAR_ACCOUNT=alice --symbol ABND --decimal 7 --name abundance --admin alice
+"""
+after_deploy = """
+{after_deploy}
+"""
+"#
+            )
+        };
+
+        env.set_environments_toml(environments_toml(
+            "mint --amount 100 --to alice\nmint --amount not-a-number --to alice",
+        ));
+        let stderr = env
+            .stellar_scaffold_env("development", true)
+            .assert()
+            .stderr_as_str();
+        assert!(stderr.contains(" -- mint --amount 100 --to alice"));
+        assert!(stderr.contains("Failed: 1"));
+
+        env.set_environments_toml(environments_toml(
+            "mint --amount 100 --to alice\nmint --amount 200 --to alice",
+        ));
+        let stderr = env
+            .stellar_scaffold_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(!stderr.contains(" -- mint --amount 100 --to alice"));
+        assert!(stderr.contains(" -- mint --amount 200 --to alice"));
+
+        let stderr = env
+            .stellar_scaffold_env("development", false)
+            .arg("--rerun-after-deploy")
+            .arg("soroban_token_contract")
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains(" -- mint --amount 100 --to alice"));
+        assert!(stderr.contains(" -- mint --amount 200 --to alice"));
+
+        let stderr = env
+            .stellar_scaffold_env("development", false)
+            .arg("--rerun-after-deploy")
+            .arg("soroban_tokn_contract")
+            .assert()
+            .failure()
+            .stderr_as_str();
+        assert!(stderr.contains(
+            "--rerun-after-deploy \"soroban_tokn_contract\" is not a contract in this workspace"
+        ));
+    });
+}
@@ -64,3 +64,51 @@ soroban_token_contract.client = false
         assert!(stderr.contains("Using lol network\n"));
     });
 }
+
+#[test]
+fn contract_network_override() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.stellar("network")
+            .args([
+                "add",
+                "lol",
+                "--rpc-url",
+                "http://localhost:8000/soroban/rpc",
+                "--network-passphrase",
+                "Standalone Network ; February 2017",
+            ])
+            .assert()
+            .success();
+
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+development.network.name = "lol"
+
+[development.contracts]
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+soroban_token_contract.client = false
+
+[development.contracts.soroban_hello_world_contract.network]
+rpc-url = "http://localhost:8000/rpc"
+network-passphrase = "Standalone Network ; February 2017"
+"#,
+        );
+
+        env.scaffold("build").assert().success();
+
+        let client = std::fs::read_to_string(
+            env.cwd
+                .join("src/contracts/soroban_hello_world_contract.ts"),
+        )
+        .unwrap();
+        assert!(client.contains("rpcUrl: 'http://localhost:8000/rpc',"));
+        assert!(client.contains("networkPassphrase: 'Standalone Network ; February 2017',"));
+        assert!(!client.contains("from './util'"));
+    });
+}
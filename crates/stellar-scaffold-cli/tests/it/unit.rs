@@ -73,3 +73,178 @@ fn init_copies_frontend_template() {
     assert!(project_path.join("src").exists());
     assert!(project_path.join("tsconfig.json").exists());
 }
+
+#[test]
+fn env_show_resolves_extends_and_default() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+[default]
+accounts = ["alice"]
+
+[default.contracts.soroban_hello_world_contract]
+constructor_args = "--greeting hi"
+
+[development]
+network = { rpc-url = "http://localhost:8000/rpc", network-passphrase = "Standalone Network ; February 2017" }
+
+[testing]
+extends = "development"
+accounts = [{ name = "bob", default = true }]
+
+[testing.contracts.soroban_hello_world_contract]
+client = false
+"#,
+        );
+
+        let stdout = env
+            .scaffold("env")
+            .args(["show", "testing"])
+            .assert()
+            .success()
+            .stdout_as_str();
+        let resolved: toml::Table = toml::from_str(&stdout).unwrap();
+        let testing = &resolved["testing"];
+        assert!(testing.get("extends").is_none());
+        assert_eq!(
+            testing["network"]["rpc-url"].as_str(),
+            Some("http://localhost:8000/rpc")
+        );
+        let accounts = testing["accounts"].as_array().unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].as_str(), Some("alice"));
+        let contract = &testing["contracts"]["soroban_hello_world_contract"];
+        assert_eq!(contract["constructor_args"].as_str(), Some("--greeting hi"));
+        assert_eq!(contract["client"].as_bool(), Some(false));
+    });
+}
+
+#[test]
+fn env_extends_replaces_contract_network_by_name() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+[development]
+network = { rpc-url = "http://localhost:8000/rpc", network-passphrase = "Standalone Network ; February 2017" }
+
+[development.contracts.soroban_hello_world_contract.network]
+rpc-url = "http://localhost:8001/rpc"
+network-passphrase = "Standalone Network ; February 2017"
+rpc-headers = [["X-Api-Key", "secret"]]
+
+[testing]
+extends = "development"
+
+[testing.contracts.soroban_hello_world_contract.network]
+name = "testnet"
+"#,
+        );
+
+        let stdout = env
+            .scaffold("env")
+            .args(["show", "testing"])
+            .assert()
+            .success()
+            .stdout_as_str();
+        let resolved: toml::Table = toml::from_str(&stdout).unwrap();
+        let network = resolved["testing"]["contracts"]["soroban_hello_world_contract"]["network"]
+            .as_table()
+            .unwrap();
+        assert_eq!(network["name"].as_str(), Some("testnet"));
+        assert!(network.get("rpc-url").is_none());
+        assert!(network.get("network-passphrase").is_none());
+        // Settings other than the network's identity are still inherited
+        assert!(network.get("rpc-headers").is_some());
+    });
+}
+
+#[test]
+fn env_extends_cycle_is_an_error() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+[development]
+extends = "testing"
+
+[testing]
+extends = "development"
+"#,
+        );
+
+        env.scaffold("env")
+            .args(["show", "development"])
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains(
+                "development -> testing -> development",
+            ));
+    });
+}
+
+#[test]
+fn env_show_rejects_default() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+[default]
+accounts = ["alice"]
+"#,
+        );
+
+        env.scaffold("env")
+            .args(["show", "default"])
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains(
+                "\"default\" is not a valid environment name",
+            ));
+    });
+}
+
+#[test]
+fn env_show_fills_in_the_same_defaults_for_every_name() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+[default]
+network = { rpc-url = "http://localhost:8000/rpc", network-passphrase = "Standalone Network ; February 2017" }
+
+[staging]
+
+[preview-main]
+
+[audit]
+mode = "pinned-ids"
+run_after_deploy = false
+"#,
+        );
+
+        let show = |name: &str| -> toml::Table {
+            let stdout = env
+                .scaffold("env")
+                .args(["show", name])
+                .assert()
+                .success()
+                .stdout_as_str();
+            toml::from_str::<toml::Table>(&stdout).unwrap()[name]
+                .as_table()
+                .unwrap()
+                .clone()
+        };
+
+        for name in ["staging", "preview-main"] {
+            let settings = show(name);
+            assert_eq!(
+                settings["mode"].as_str(),
+                Some("deploy-from-source"),
+                "{name}"
+            );
+            assert_eq!(settings["allow_http"].as_bool(), Some(false), "{name}");
+            assert_eq!(settings["run_after_deploy"].as_bool(), Some(true), "{name}");
+        }
+
+        let audit = show("audit");
+        assert_eq!(audit["mode"].as_str(), Some("pinned-ids"));
+        assert_eq!(audit["run_after_deploy"].as_bool(), Some(false));
+    });
+}
@@ -13,22 +13,31 @@ pub(crate) fn manifest() -> std::path::PathBuf {
 /// Generates a contract Client for a given contract.
 /// It is expected that the name should be the same as the published contract or a contract in your current workspace.
 ///
+/// The wasm is loaded from `target/stellar`, at the path `stellar_build::stellar_wasm_out_file`
+/// gives the name. Scoped names such as `@ahalabs/token` are loaded from `<namespace>/<name>.wasm`
+/// there, and generate a module named after the unscoped name, e.g. `token`.
+///
 /// # Panics
 ///
 /// This function may panic in the following situations:
-/// - If `stellar_build::get_target_dir()` fails to retrieve the target directory
+/// - If `stellar_build::cargo_target_dir()` fails to retrieve the target directory
 /// - If the input tokens cannot be parsed as a valid identifier
 /// - If the directory path cannot be canonicalized
 /// - If the canonical path cannot be converted to a string
 #[proc_macro]
 pub fn import_contract_client(tokens: TokenStream) -> TokenStream {
     let cargo_file = manifest();
-    let mut dir = stellar_build::get_target_dir(&cargo_file)
-        .unwrap()
-        .join(tokens.to_string());
-    let name = syn::parse::<syn::Ident>(tokens).expect("The input must be a valid identifier");
-    dir.set_extension("wasm");
-    let binding = dir.canonicalize().unwrap();
+    // Tokens of a scoped name are separated by whitespace, e.g. `@ ahalabs / token`
+    let input: String = tokens.to_string().split_whitespace().collect();
+    let name = match input.strip_prefix('@').and_then(|s| s.split_once('/')) {
+        Some((_, name)) => name.replace('-', "_"),
+        None => input.clone(),
+    };
+    let target_dir = stellar_build::cargo_target_dir(&cargo_file).unwrap();
+    let wasm = stellar_build::stellar_wasm_out_file(&target_dir, &input);
+    let name = syn::parse_str::<syn::Ident>(&name)
+        .expect("The input must be a valid identifier or a scoped name like `@namespace/name`");
+    let binding = wasm.canonicalize().unwrap();
     let file = binding.to_str().unwrap();
     assert!(
         std::path::PathBuf::from(file).exists(),
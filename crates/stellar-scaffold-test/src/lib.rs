@@ -7,7 +7,7 @@ mod common;
 mod registry;
 
 // Re-export the main components that tests will commonly use
-pub use common::{AssertExt, TestEnv, find_binary};
+pub use common::{AssertExt, TestEnv, find_binary, find_registry_wasm};
 pub use registry::RegistryTest;
 
 // If we need to expose any common test constants or utilities, they can go here